@@ -6,17 +6,13 @@ fn parse_benchmark(c: &mut Criterion) {
 
     c.bench_function("parse_simple_json", |b| {
         b.iter(|| {
-            // TODO: Uncomment when parser is implemented
-            // rdn::parse(black_box(simple_json)).unwrap()
-            black_box(simple_json);
+            rdn::parse(black_box(simple_json)).unwrap()
         })
     });
 
     c.bench_function("parse_rdn_extended", |b| {
         b.iter(|| {
-            // TODO: Uncomment when parser is implemented
-            // rdn::parse(black_box(rdn_with_types)).unwrap()
-            black_box(rdn_with_types);
+            rdn::parse(black_box(rdn_with_types)).unwrap()
         })
     });
 }
@@ -0,0 +1,376 @@
+use std::ops::Index;
+
+use crate::types::*;
+
+/// What lookups that miss return a reference to.
+static NULL: RdnValue = RdnValue::Null;
+
+/// A chainable, non-panicking view into a value tree, created by
+/// [`RdnValue::try_get`].
+///
+/// Each step either moves to a child or turns the accessor empty, and every
+/// step on an empty accessor stays empty, so a chain such as
+/// `v.try_get().key("a").index(0).key("b").as_str()` yields `None` as soon as
+/// any step is missing or has the wrong type.
+#[derive(Debug, Clone, Copy)]
+pub struct RdnAccessor<'a> {
+    current: Option<&'a RdnValue>,
+}
+
+impl<'a> RdnAccessor<'a> {
+    /// Steps into the member `key` of an Object, or the entry of a Map whose
+    /// key is the string `key`. With duplicate keys the first entry wins.
+    pub fn key(self, key: &str) -> Self {
+        let current = self.current.and_then(|value| match value {
+            RdnValue::Object(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            RdnValue::Map(entries) => entries
+                .iter()
+                .find(|(k, _)| matches!(k, RdnValue::String(s) if s == key))
+                .map(|(_, v)| v),
+            _ => None,
+        });
+        RdnAccessor { current }
+    }
+
+    /// Steps into the `index`th element of an Array (zero-based).
+    pub fn index(self, index: usize) -> Self {
+        let current = self.current.and_then(|value| match value {
+            RdnValue::Array(items) => items.get(index),
+            _ => None,
+        });
+        RdnAccessor { current }
+    }
+
+    /// Returns the value reached, if every step succeeded.
+    pub fn value(self) -> Option<&'a RdnValue> {
+        self.current
+    }
+
+    /// Returns the contents of a `String`.
+    pub fn as_str(self) -> Option<&'a str> {
+        match self.current? {
+            RdnValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of a `Number`.
+    pub fn as_f64(self) -> Option<f64> {
+        match self.current? {
+            RdnValue::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of a `Bool`.
+    pub fn as_bool(self) -> Option<bool> {
+        match self.current? {
+            RdnValue::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// Returns the `BigInt` held by a `BigInt` value.
+    pub fn as_bigint(self) -> Option<&'a BigInt> {
+        match self.current? {
+            RdnValue::BigInt(bi) => Some(bi),
+            _ => None,
+        }
+    }
+
+    /// Returns the bytes of a `Binary`.
+    pub fn as_bytes(self) -> Option<&'a [u8]> {
+        match self.current? {
+            RdnValue::Binary(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    /// Returns the elements of an `Array`.
+    pub fn as_array(self) -> Option<&'a [RdnValue]> {
+        match self.current? {
+            RdnValue::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the value reached is `Null`. A failed chain is not null.
+    pub fn is_null(self) -> bool {
+        matches!(self.current, Some(RdnValue::Null))
+    }
+}
+
+impl RdnValue {
+    /// Returns the first value in `values` that is not `Null`, or `Null` if
+    /// there is none.
+    ///
+    /// Intended for layered config lookups, where later entries are fallbacks
+    /// for earlier ones:
+    ///
+    /// ```
+    /// use rdn::RdnValue;
+    ///
+    /// let user = RdnValue::Null;
+    /// let default = RdnValue::Number(8080.0);
+    /// assert_eq!(RdnValue::coalesce(&[&user, &default]), &default);
+    /// ```
+    pub fn coalesce<'a>(values: &[&'a RdnValue]) -> &'a RdnValue {
+        values.iter().copied().find(|v| !matches!(v, RdnValue::Null)).unwrap_or(&NULL)
+    }
+
+    /// Starts a chained, non-panicking lookup rooted at this value.
+    ///
+    /// See [`RdnAccessor`] for the available steps. This is the fluent
+    /// counterpart to [`RdnValue::select`], convenient when keys and indices
+    /// are only known at run time.
+    pub fn try_get(&self) -> RdnAccessor<'_> {
+        RdnAccessor { current: Some(self) }
+    }
+
+    /// Iterates mutably over the elements of an `Array`. Yields nothing for
+    /// any other variant.
+    pub fn array_iter_mut(&mut self) -> impl Iterator<Item = &mut RdnValue> {
+        let items: &mut [RdnValue] = match self {
+            RdnValue::Array(items) => items,
+            _ => &mut [],
+        };
+        items.iter_mut()
+    }
+
+    /// Iterates over the entries of an `Object` in order, with each key
+    /// borrowed immutably and its value mutably. Yields nothing for any other
+    /// variant.
+    pub fn object_iter_mut(&mut self) -> impl Iterator<Item = (&str, &mut RdnValue)> {
+        let entries: &mut [(String, RdnValue)] = match self {
+            RdnValue::Object(entries) => entries,
+            _ => &mut [],
+        };
+        entries.iter_mut().map(|(k, v)| (k.as_str(), v))
+    }
+
+    /// Returns the member `key` of an `Object`, or `None` if it is missing or
+    /// this is not an `Object`. With duplicate keys the first entry wins.
+    pub fn get(&self, key: &str) -> Option<&RdnValue> {
+        match self {
+            RdnValue::Object(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Mutable counterpart to [`RdnValue::get`].
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut RdnValue> {
+        match self {
+            RdnValue::Object(entries) => entries.iter_mut().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Returns the `index`th element of an `Array`, or `None` if it is out of
+    /// range or this is not an `Array`.
+    pub fn get_index(&self, index: usize) -> Option<&RdnValue> {
+        match self {
+            RdnValue::Array(items) => items.get(index),
+            _ => None,
+        }
+    }
+
+    /// Mutable counterpart to [`RdnValue::get_index`].
+    pub fn get_index_mut(&mut self, index: usize) -> Option<&mut RdnValue> {
+        match self {
+            RdnValue::Array(items) => items.get_mut(index),
+            _ => None,
+        }
+    }
+}
+
+/// `value["key"]` looks up the member `key` of an Object, the first one if
+/// the key is repeated.
+///
+/// Like `serde_json`, a miss does not panic: a missing key, or indexing
+/// anything but an Object, gives `Null`. So `value["a"]["b"]` is `Null`
+/// whenever any step is absent. Use [`RdnValue::try_get`] to tell a missing
+/// member from one that is present and `null`, or [`RdnValue::get`].
+impl Index<&str> for RdnValue {
+    type Output = RdnValue;
+
+    fn index(&self, key: &str) -> &RdnValue {
+        self.get(key).unwrap_or(&NULL)
+    }
+}
+
+/// `value[i]` looks up the `i`th element of an Array, giving `Null` if it is
+/// out of range or the value is not an Array; see `Index<&str>`.
+impl Index<usize> for RdnValue {
+    type Output = RdnValue;
+
+    fn index(&self, index: usize) -> &RdnValue {
+        self.get_index(index).unwrap_or(&NULL)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> RdnValue {
+        RdnValue::Object(vec![(
+            "servers".to_string(),
+            RdnValue::Array(vec![RdnValue::Object(vec![
+                ("host".to_string(), RdnValue::String("a.example".to_string())),
+                ("port".to_string(), RdnValue::Number(8080.0)),
+                ("tls".to_string(), RdnValue::Bool(true)),
+                ("proxy".to_string(), RdnValue::Null),
+                (
+                    "labels".to_string(),
+                    RdnValue::Map(vec![(RdnValue::String("env".to_string()), RdnValue::String("prod".to_string()))]),
+                ),
+            ])]),
+        )])
+    }
+
+    #[test]
+    fn try_get_follows_chain() {
+        let v = sample();
+        let server = v.try_get().key("servers").index(0);
+        assert_eq!(server.key("host").as_str(), Some("a.example"));
+        assert_eq!(server.key("port").as_f64(), Some(8080.0));
+        assert_eq!(server.key("tls").as_bool(), Some(true));
+        assert!(server.key("proxy").is_null());
+    }
+
+    #[test]
+    fn try_get_reads_string_map_keys() {
+        let v = sample();
+        assert_eq!(v.try_get().key("servers").index(0).key("labels").key("env").as_str(), Some("prod"));
+    }
+
+    #[test]
+    fn try_get_missing_step_is_none() {
+        let v = sample();
+        assert_eq!(v.try_get().key("nope").index(0).key("host").value(), None);
+        assert_eq!(v.try_get().key("servers").index(5).value(), None);
+        assert!(!v.try_get().key("nope").is_null());
+    }
+
+    #[test]
+    fn try_get_type_mismatch_is_none() {
+        let v = sample();
+        assert_eq!(v.try_get().index(0).value(), None);
+        assert_eq!(v.try_get().key("servers").key("host").value(), None);
+        assert_eq!(v.try_get().key("servers").index(0).key("port").as_str(), None);
+    }
+
+    #[test]
+    fn coalesce_picks_first_non_null() {
+        let a = RdnValue::Null;
+        let b = RdnValue::Bool(false);
+        let c = RdnValue::Number(1.0);
+        assert_eq!(RdnValue::coalesce(&[&a, &b, &c]), &b);
+    }
+
+    #[test]
+    fn coalesce_all_null_or_empty_is_null() {
+        assert_eq!(RdnValue::coalesce(&[&RdnValue::Null, &RdnValue::Null]), &RdnValue::Null);
+        assert_eq!(RdnValue::coalesce(&[]), &RdnValue::Null);
+    }
+
+    #[test]
+    fn try_get_root_value() {
+        let v = RdnValue::Binary(vec![1, 2]);
+        assert_eq!(v.try_get().as_bytes(), Some(&[1u8, 2][..]));
+        assert_eq!(v.try_get().value(), Some(&v));
+    }
+
+    // --- Mutable iterator tests ---
+
+    #[test]
+    fn array_iter_mut_updates_elements() {
+        let mut v = RdnValue::Array(vec![RdnValue::Number(1.0), RdnValue::Number(2.0)]);
+        for item in v.array_iter_mut() {
+            if let RdnValue::Number(n) = item {
+                *n *= 10.0;
+            }
+        }
+        assert_eq!(v, RdnValue::Array(vec![RdnValue::Number(10.0), RdnValue::Number(20.0)]));
+    }
+
+    #[test]
+    fn object_iter_mut_yields_keys_and_mutable_values() {
+        let mut v = RdnValue::Object(vec![("a".to_string(), RdnValue::Null), ("b".to_string(), RdnValue::Null)]);
+        for (key, value) in v.object_iter_mut() {
+            *value = RdnValue::String(key.to_uppercase());
+        }
+        assert_eq!(
+            v,
+            RdnValue::Object(vec![
+                ("a".to_string(), RdnValue::String("A".to_string())),
+                ("b".to_string(), RdnValue::String("B".to_string())),
+            ])
+        );
+    }
+
+    #[test]
+    fn iter_mut_is_empty_for_other_variants() {
+        let mut set = RdnValue::Set(vec![RdnValue::Null]);
+        assert_eq!(set.array_iter_mut().count(), 0);
+        assert_eq!(set.object_iter_mut().count(), 0);
+    }
+
+    // --- Index tests ---
+
+    #[test]
+    fn index_reads_nested_values() {
+        let v = sample();
+        assert_eq!(v["servers"][0]["host"], RdnValue::String("a.example".to_string()));
+        assert_eq!(v["servers"][0]["port"], RdnValue::Number(8080.0));
+        let dup = RdnValue::Object(vec![("k".to_string(), RdnValue::Bool(true)), ("k".to_string(), RdnValue::Null)]);
+        assert_eq!(dup["k"], RdnValue::Bool(true));
+    }
+
+    #[test]
+    fn index_misses_give_null() {
+        let v = sample();
+        assert_eq!(v["missing"], RdnValue::Null);
+        assert_eq!(v["missing"]["deeper"][3], RdnValue::Null);
+        assert_eq!(v["servers"][1], RdnValue::Null);
+        assert_eq!(v["servers"]["host"], RdnValue::Null);
+        assert_eq!(v[0], RdnValue::Null);
+        // A Map is not an Object, even with string keys.
+        assert_eq!(v["servers"][0]["labels"]["env"], RdnValue::Null);
+    }
+
+    // --- get tests ---
+
+    #[test]
+    fn get_finds_members_and_elements() {
+        let v = sample();
+        let server = v.get("servers").and_then(|s| s.get_index(0)).unwrap();
+        assert_eq!(server.get("tls"), Some(&RdnValue::Bool(true)));
+        assert_eq!(server.get("proxy"), Some(&RdnValue::Null));
+        let dup = RdnValue::Object(vec![("k".to_string(), RdnValue::Bool(true)), ("k".to_string(), RdnValue::Null)]);
+        assert_eq!(dup.get("k"), Some(&RdnValue::Bool(true)));
+    }
+
+    #[test]
+    fn get_misses_are_none() {
+        let v = sample();
+        assert_eq!(v.get("missing"), None);
+        assert_eq!(v.get_index(0), None);
+        assert_eq!(v["servers"].get_index(1), None);
+        assert_eq!(v["servers"].get("host"), None);
+        assert_eq!(v["servers"][0]["labels"].get("env"), None);
+    }
+
+    #[test]
+    fn get_mut_updates_in_place() {
+        let mut v = sample();
+        let server = v.get_mut("servers").and_then(|s| s.get_index_mut(0)).unwrap();
+        *server.get_mut("port").unwrap() = RdnValue::Number(443.0);
+        assert!(server.get_mut("missing").is_none());
+        assert_eq!(v["servers"][0]["port"], RdnValue::Number(443.0));
+
+        let mut dup = RdnValue::Object(vec![("k".to_string(), RdnValue::Null), ("k".to_string(), RdnValue::Null)]);
+        *dup.get_mut("k").unwrap() = RdnValue::Bool(true);
+        assert_eq!(dup, RdnValue::Object(vec![("k".to_string(), RdnValue::Bool(true)), ("k".to_string(), RdnValue::Null)]));
+    }
+}
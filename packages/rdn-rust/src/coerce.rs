@@ -0,0 +1,224 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::date::parse_iso_datetime;
+use crate::encoding::decode_base64;
+use crate::parser::{parse, ParseError};
+use crate::types::*;
+
+/// An error from [`parse_json_with_coercions`] or
+/// [`RdnValue::apply_coercions`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CoerceError {
+    /// The input is not valid RDN.
+    Parse(ParseError),
+    /// A coercion could not be applied. `path` is the concrete path of the
+    /// offending value, e.g. `$.users[1].created`, or the query itself when
+    /// the query is malformed.
+    Coerce { path: String, reason: String },
+}
+
+impl fmt::Display for CoerceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CoerceError::Parse(e) => e.fmt(f),
+            CoerceError::Coerce { path, reason } => write!(f, "cannot coerce {path}: {reason}"),
+        }
+    }
+}
+
+impl Error for CoerceError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CoerceError::Parse(e) => Some(e),
+            CoerceError::Coerce { .. } => None,
+        }
+    }
+}
+
+/// The extended type a JSON string should be upgraded to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Coercion {
+    /// An ISO 8601 date (`2024-01-15`) or date-time (`2024-01-15T10:30:00Z`,
+    /// offsets allowed) becomes a `Date`.
+    Date,
+    /// A string of digits with an optional leading `-` becomes a `BigInt`.
+    BigInt,
+    /// Padded standard base64 becomes `Binary`.
+    Binary,
+}
+
+impl Coercion {
+    fn apply(self, s: &str) -> Result<RdnValue, String> {
+        match self {
+            Coercion::Date => parse_iso_datetime(s).map(|millis| RdnValue::Date(RdnDate { millis })),
+            Coercion::BigInt => BigInt::new(s).map(RdnValue::BigInt),
+            Coercion::Binary => decode_base64(s).map(RdnValue::Binary),
+        }
+    }
+
+    fn target(self) -> &'static str {
+        match self {
+            Coercion::Date => "Date",
+            Coercion::BigInt => "BigInt",
+            Coercion::Binary => "Binary",
+        }
+    }
+}
+
+/// Parses `input` and then upgrades the string values at the given paths to
+/// extended types.
+///
+/// Paths use the query syntax of [`RdnValue::select`], so `$.created` or
+/// `$.users[*].id` both work. See [`RdnValue::apply_coercions`] for the rules.
+///
+/// # Errors
+///
+/// Returns [`CoerceError::Parse`] if `input` is malformed, or
+/// [`CoerceError::Coerce`] naming the offending path.
+pub fn parse_json_with_coercions(input: &str, coercions: &[(&str, Coercion)]) -> Result<RdnValue, CoerceError> {
+    let mut value = parse(input).map_err(CoerceError::Parse)?;
+    value.apply_coercions(coercions)?;
+    Ok(value)
+}
+
+impl RdnValue {
+    /// Converts the string values at the given paths into extended types, in
+    /// place.
+    ///
+    /// A path that matches nothing is skipped, so optional fields need no
+    /// special handling. A matched value that already has the target type is
+    /// left alone. Any other non-string value, or a string that is not valid
+    /// for the target type, is a [`CoerceError::Coerce`] naming the concrete
+    /// path, e.g. `cannot coerce $.users[1].created: invalid Date: month must
+    /// be 01-12, got 13`. On error the value may be partially coerced.
+    pub fn apply_coercions(&mut self, coercions: &[(&str, Coercion)]) -> Result<(), CoerceError> {
+        for &(query, coercion) in coercions {
+            let paths = self.locate(query).map_err(|reason| CoerceError::Coerce { path: query.to_string(), reason })?;
+            for path in paths {
+                let Some(target) = self.at_path_mut(&path) else {
+                    continue;
+                };
+                let coerced = match &*target {
+                    RdnValue::String(s) => coercion.apply(s).map_err(|e| format!("invalid {}: {e}", coercion.target())),
+                    other if other.type_name() == coercion.target() => continue,
+                    other => Err(format!("expected String or {}, found {}", coercion.target(), other.type_name())),
+                };
+                match coerced {
+                    Ok(value) => *target = value,
+                    Err(reason) => return Err(CoerceError::Coerce { path: self.describe_path(&path), reason }),
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{obj, s};
+
+    #[test]
+    fn coerce_date() {
+        let mut v = obj(vec![("created", s("2024-01-15T10:30:00.000Z"))]);
+        v.apply_coercions(&[("$.created", Coercion::Date)]).unwrap();
+        assert_eq!(v, obj(vec![("created", RdnValue::Date(RdnDate { millis: 1_705_314_600_000.0 }))]));
+    }
+
+    #[test]
+    fn coerce_bigint_and_binary() {
+        let mut v = obj(vec![("id", s("9007199254740993")), ("blob", s("SGk="))]);
+        v.apply_coercions(&[("$.id", Coercion::BigInt), ("$.blob", Coercion::Binary)]).unwrap();
+        assert_eq!(
+            v,
+            obj(vec![
+                ("id", RdnValue::BigInt(BigInt::new("9007199254740993").unwrap())),
+                ("blob", RdnValue::Binary(b"Hi".to_vec())),
+            ])
+        );
+    }
+
+    #[test]
+    fn coerce_wildcard_path() {
+        let mut v = obj(vec![(
+            "users",
+            RdnValue::Array(vec![obj(vec![("id", s("1"))]), obj(vec![("id", s("2"))])]),
+        )]);
+        v.apply_coercions(&[("$.users[*].id", Coercion::BigInt)]).unwrap();
+        assert_eq!(v.select("$.users[1].id"), vec![&RdnValue::BigInt(BigInt::new("2").unwrap())]);
+    }
+
+    #[test]
+    fn coerce_missing_path_is_skipped() {
+        let mut v = obj(vec![("a", s("x"))]);
+        let before = v.clone();
+        v.apply_coercions(&[("$.missing", Coercion::Date)]).unwrap();
+        assert_eq!(v, before);
+    }
+
+    #[test]
+    fn coerce_already_coerced_is_noop() {
+        let mut v = obj(vec![("d", RdnValue::Date(RdnDate { millis: 0.0 }))]);
+        v.apply_coercions(&[("$.d", Coercion::Date)]).unwrap();
+        assert_eq!(v, obj(vec![("d", RdnValue::Date(RdnDate { millis: 0.0 }))]));
+    }
+
+    #[test]
+    fn coerce_invalid_string_reports_path() {
+        let mut v = obj(vec![(
+            "users",
+            RdnValue::Array(vec![obj(vec![("created", s("2024-01-15"))]), obj(vec![("created", s("2024-13-01"))])]),
+        )]);
+        let err = v.apply_coercions(&[("$.users[*].created", Coercion::Date)]).unwrap_err();
+        assert!(err.to_string().starts_with("cannot coerce $.users[1].created: invalid Date:"), "{err}");
+        let CoerceError::Coerce { path, .. } = err else { panic!("{err:?}") };
+        assert_eq!(path, "$.users[1].created");
+    }
+
+    #[test]
+    fn coerce_non_string_reports_type() {
+        let mut v = obj(vec![("id", RdnValue::Number(42.0))]);
+        let err = v.apply_coercions(&[("$.id", Coercion::BigInt)]).unwrap_err();
+        assert_eq!(
+            err,
+            CoerceError::Coerce { path: "$.id".to_string(), reason: "expected String or BigInt, found Number".to_string() }
+        );
+        assert_eq!(err.to_string(), "cannot coerce $.id: expected String or BigInt, found Number");
+    }
+
+    #[test]
+    fn parse_json_with_coercions_end_to_end() {
+        let v = parse_json_with_coercions(r#"{"id": "42", "at": "2024-01-15"}"#, &[
+            ("$.id", Coercion::BigInt),
+            ("$.at", Coercion::Date),
+        ])
+        .unwrap();
+        assert_eq!(
+            v,
+            obj(vec![
+                ("id", RdnValue::BigInt(BigInt::new("42").unwrap())),
+                ("at", RdnValue::Date(RdnDate { millis: 1_705_276_800_000.0 })),
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_json_with_coercions_errors() {
+        let err = parse_json_with_coercions("{\"id\": }", &[("$.id", Coercion::BigInt)]).unwrap_err();
+        let CoerceError::Parse(parse_err) = &err else { panic!("{err:?}") };
+        assert_eq!(parse_err.offset(), 7);
+        assert!(err.source().is_some());
+
+        let err = parse_json_with_coercions("{\"id\": \"x\"}", &[("$.id", Coercion::BigInt)]).unwrap_err();
+        assert!(matches!(&err, CoerceError::Coerce { path, .. } if path == "$.id"), "{err:?}");
+        assert!(err.source().is_none());
+    }
+
+    #[test]
+    fn coerce_malformed_query_is_error() {
+        let mut v = obj(vec![]);
+        let err = v.apply_coercions(&[("created", Coercion::Date)]).unwrap_err();
+        assert!(matches!(&err, CoerceError::Coerce { path, .. } if path == "created"), "{err:?}");
+    }
+}
@@ -0,0 +1,472 @@
+use crate::encoding::percent_encode;
+use crate::types::*;
+
+fn mismatch(expected: &str, found: &RdnValue) -> String {
+    format!("expected {expected}, found {}", found.type_name())
+}
+
+/// Returns `n` as an `i64` if it is an integer within `i64` range.
+pub(crate) fn exact_i64(n: f64) -> Option<i64> {
+    // -2^63 is exactly representable; 2^63 is the first value past i64::MAX.
+    const RANGE: std::ops::Range<f64> = -9_223_372_036_854_775_808.0..9_223_372_036_854_775_808.0;
+    (n.fract() == 0.0 && RANGE.contains(&n)).then_some(n as i64)
+}
+
+/// Appends `key=value` pairs for `value` to `out`, expanding Arrays and
+/// Objects into bracketed keys. Returns `None` on an unsupported type.
+fn push_query_pairs(key: &str, value: &RdnValue, out: &mut String) -> Option<()> {
+    let scalar = match value {
+        RdnValue::Null => String::new(),
+        RdnValue::Bool(b) => b.to_string(),
+        RdnValue::Number(_) => value.to_string(),
+        RdnValue::BigInt(bi) => bi.value().to_string(),
+        RdnValue::Decimal(d) => d.value().to_string(),
+        RdnValue::String(s) => s.clone(),
+        RdnValue::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                push_query_pairs(&format!("{key}[{i}]"), item, out)?;
+            }
+            return Some(());
+        }
+        RdnValue::Object(entries) => {
+            for (k, v) in entries {
+                push_query_pairs(&format!("{key}[{k}]"), v, out)?;
+            }
+            return Some(());
+        }
+        _ => return None,
+    };
+    if !out.is_empty() {
+        out.push('&');
+    }
+    percent_encode(key, out);
+    out.push('=');
+    percent_encode(&scalar, out);
+    Some(())
+}
+
+impl TryFrom<RdnValue> for String {
+    type Error = String;
+
+    fn try_from(value: RdnValue) -> Result<Self, Self::Error> {
+        match value {
+            RdnValue::String(s) => Ok(s),
+            other => Err(mismatch("String", &other)),
+        }
+    }
+}
+
+impl TryFrom<RdnValue> for f64 {
+    type Error = String;
+
+    fn try_from(value: RdnValue) -> Result<Self, Self::Error> {
+        match value {
+            RdnValue::Number(n) => Ok(n),
+            other => Err(mismatch("Number", &other)),
+        }
+    }
+}
+
+/// Accepts a `Number` holding an exact integer in `i64` range, or a `BigInt`
+/// that fits in `i64`.
+impl TryFrom<RdnValue> for i64 {
+    type Error = String;
+
+    fn try_from(value: RdnValue) -> Result<Self, Self::Error> {
+        match value {
+            RdnValue::Number(n) => exact_i64(n).ok_or_else(|| format!("Number {n} is not an integer in i64 range")),
+            RdnValue::BigInt(bi) => bi
+                .value()
+                .parse()
+                .map_err(|_| format!("BigInt {} is out of i64 range", bi.value())),
+            other => Err(mismatch("Number or BigInt", &other)),
+        }
+    }
+}
+
+impl TryFrom<RdnValue> for bool {
+    type Error = String;
+
+    fn try_from(value: RdnValue) -> Result<Self, Self::Error> {
+        match value {
+            RdnValue::Bool(b) => Ok(b),
+            other => Err(mismatch("Bool", &other)),
+        }
+    }
+}
+
+impl TryFrom<RdnValue> for Vec<u8> {
+    type Error = String;
+
+    fn try_from(value: RdnValue) -> Result<Self, Self::Error> {
+        match value {
+            RdnValue::Binary(bytes) => Ok(bytes),
+            other => Err(mismatch("Binary", &other)),
+        }
+    }
+}
+
+impl TryFrom<RdnValue> for Vec<RdnValue> {
+    type Error = String;
+
+    fn try_from(value: RdnValue) -> Result<Self, Self::Error> {
+        match value {
+            RdnValue::Array(items) => Ok(items),
+            other => Err(mismatch("Array", &other)),
+        }
+    }
+}
+
+/// Converts to a `Number`. An `f64` holds every integer up to 2^53 in
+/// magnitude exactly; beyond that the value rounds to the nearest `f64`, so
+/// `i64::MAX` becomes 2^63. Use [`BigInt`] to keep every digit.
+impl From<i64> for RdnValue {
+    fn from(n: i64) -> Self {
+        RdnValue::Number(n as f64)
+    }
+}
+
+impl From<f64> for RdnValue {
+    fn from(n: f64) -> Self {
+        RdnValue::Number(n)
+    }
+}
+
+impl From<bool> for RdnValue {
+    fn from(b: bool) -> Self {
+        RdnValue::Bool(b)
+    }
+}
+
+impl From<&str> for RdnValue {
+    fn from(s: &str) -> Self {
+        RdnValue::String(s.to_string())
+    }
+}
+
+impl From<String> for RdnValue {
+    fn from(s: String) -> Self {
+        RdnValue::String(s)
+    }
+}
+
+/// Converts to `Binary`.
+impl From<Vec<u8>> for RdnValue {
+    fn from(bytes: Vec<u8>) -> Self {
+        RdnValue::Binary(bytes)
+    }
+}
+
+/// Converts to an `Array`.
+impl From<Vec<RdnValue>> for RdnValue {
+    fn from(items: Vec<RdnValue>) -> Self {
+        RdnValue::Array(items)
+    }
+}
+
+impl RdnValue {
+    /// Returns this value retagged as a `Map`.
+    ///
+    /// An Object becomes a Map with the same entries in the same order, each
+    /// key wrapped in `RdnValue::String`. Any other value, including a Map, is
+    /// returned as a clone. Only the top level is retagged; nested Objects stay
+    /// Objects.
+    pub fn as_map_view(&self) -> RdnValue {
+        match self {
+            RdnValue::Object(entries) => {
+                RdnValue::Map(entries.iter().map(|(k, v)| (RdnValue::String(k.clone()), v.clone())).collect())
+            }
+            other => other.clone(),
+        }
+    }
+
+    /// Returns this value retagged as an `Object`.
+    ///
+    /// A Map whose keys are all `String`s becomes an Object with the same
+    /// entries in the same order. Any other value, including an Object, is
+    /// returned as a clone. Only the top level is retagged; nested Maps stay
+    /// Maps.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the Map has a non-string key, naming its index and type.
+    pub fn as_object_view(&self) -> Result<RdnValue, String> {
+        match self {
+            RdnValue::Map(entries) => entries
+                .iter()
+                .enumerate()
+                .map(|(i, (k, v))| match k {
+                    RdnValue::String(key) => Ok((key.clone(), v.clone())),
+                    other => Err(format!("Map key at index {i} is {}, not String", other.type_name())),
+                })
+                .collect::<Result<_, _>>()
+                .map(RdnValue::Object),
+            other => Ok(other.clone()),
+        }
+    }
+
+    /// Renders an Object as a URL query string such as `a=1&b=x%20y`, or
+    /// returns `None` if this is not an Object or holds an unsupported value.
+    ///
+    /// Entries are written in order as `key=value`, joined by `&`. Scalars are
+    /// rendered as text: `Null` as an empty value, `Bool` as `true`/`false`,
+    /// `Number` as in RDN output (`42`, `0.5`, `NaN`), `BigInt` and `Decimal`
+    /// as their digits without suffix, and `String` as-is. Nested values use
+    /// bracket notation: Array elements become `key[0]`, `key[1]`, … and Object
+    /// members become `key[member]`; an empty container contributes nothing.
+    /// Any other type (Map, Set, Date, Binary, …) anywhere in the tree yields
+    /// `None`.
+    ///
+    /// Keys and values are percent-encoded as UTF-8, leaving only the RFC 3986
+    /// unreserved characters `A-Z a-z 0-9 - . _ ~` literal. Brackets are encoded
+    /// too, so `tags[0]` is written `tags%5B0%5D`, and spaces become `%20`.
+    pub fn to_query_string(&self) -> Option<String> {
+        let RdnValue::Object(entries) = self else {
+            return None;
+        };
+        let mut out = String::new();
+        for (key, value) in entries {
+            push_query_pairs(key, value, &mut out)?;
+        }
+        Some(out)
+    }
+
+    /// Returns a Map as an `Array` of `[key, value]` pairs, or `None` if this
+    /// is not a Map.
+    ///
+    /// Each entry becomes a two-element `Array` holding the key and then the
+    /// value, in entry order, so `Map{1 => "a"}` becomes `[[1, "a"]]`. Unlike
+    /// [`RdnValue::as_object_view`], any key type is allowed. Keys and values
+    /// are cloned as-is; nested Maps are not converted.
+    pub fn map_to_pairs(&self) -> Option<RdnValue> {
+        match self {
+            RdnValue::Map(entries) => Some(RdnValue::Array(
+                entries.iter().map(|(k, v)| RdnValue::Array(vec![k.clone(), v.clone()])).collect(),
+            )),
+            _ => None,
+        }
+    }
+
+    /// Rebuilds a Map from the `[key, value]` pair structure produced by
+    /// [`RdnValue::map_to_pairs`].
+    ///
+    /// # Errors
+    ///
+    /// Fails if this is not an `Array`, or if any element is not a
+    /// two-element `Array`.
+    pub fn pairs_to_map(&self) -> Result<RdnValue, String> {
+        let RdnValue::Array(pairs) = self else {
+            return Err(mismatch("Array", self));
+        };
+        pairs
+            .iter()
+            .enumerate()
+            .map(|(i, pair)| match pair {
+                RdnValue::Array(kv) if kv.len() == 2 => Ok((kv[0].clone(), kv[1].clone())),
+                _ => Err(format!("element {i} is not a [key, value] pair")),
+            })
+            .collect::<Result<_, _>>()
+            .map(RdnValue::Map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{obj, s};
+
+    #[test]
+    fn try_into_string() {
+        let s: String = RdnValue::String("hi".to_string()).try_into().unwrap();
+        assert_eq!(s, "hi");
+        let err = String::try_from(RdnValue::Number(1.0)).unwrap_err();
+        assert_eq!(err, "expected String, found Number");
+    }
+
+    #[test]
+    fn try_into_f64() {
+        assert_eq!(f64::try_from(RdnValue::Number(1.5)).unwrap(), 1.5);
+        assert_eq!(f64::try_from(RdnValue::Null).unwrap_err(), "expected Number, found Null");
+    }
+
+    #[test]
+    fn try_into_i64_from_number() {
+        assert_eq!(i64::try_from(RdnValue::Number(8080.0)).unwrap(), 8080);
+        assert_eq!(i64::try_from(RdnValue::Number(-3.0)).unwrap(), -3);
+        assert!(i64::try_from(RdnValue::Number(1.5)).is_err());
+        assert!(i64::try_from(RdnValue::Number(f64::NAN)).is_err());
+        assert!(i64::try_from(RdnValue::Number(f64::INFINITY)).is_err());
+        assert!(i64::try_from(RdnValue::Number(1e19)).is_err());
+        assert_eq!(i64::try_from(RdnValue::Number(-9.223_372_036_854_776e18)).unwrap(), i64::MIN);
+    }
+
+    #[test]
+    fn try_into_i64_from_bigint() {
+        let big = |s: &str| RdnValue::BigInt(BigInt::new(s).unwrap());
+        assert_eq!(i64::try_from(big("9223372036854775807")).unwrap(), i64::MAX);
+        assert_eq!(
+            i64::try_from(big("9223372036854775808")).unwrap_err(),
+            "BigInt 9223372036854775808 is out of i64 range"
+        );
+    }
+
+    #[test]
+    fn try_into_i64_wrong_type() {
+        assert_eq!(
+            i64::try_from(RdnValue::String("1".to_string())).unwrap_err(),
+            "expected Number or BigInt, found String"
+        );
+    }
+
+    #[test]
+    fn try_into_bool() {
+        assert!(bool::try_from(RdnValue::Bool(true)).unwrap());
+        assert_eq!(bool::try_from(RdnValue::Null).unwrap_err(), "expected Bool, found Null");
+    }
+
+    #[test]
+    fn try_into_bytes() {
+        let bytes: Vec<u8> = RdnValue::Binary(vec![1, 2]).try_into().unwrap();
+        assert_eq!(bytes, [1, 2]);
+        assert_eq!(Vec::<u8>::try_from(RdnValue::Array(vec![])).unwrap_err(), "expected Binary, found Array");
+    }
+
+    #[test]
+    fn try_into_array() {
+        let items: Vec<RdnValue> = RdnValue::Array(vec![RdnValue::Null]).try_into().unwrap();
+        assert_eq!(items, [RdnValue::Null]);
+        assert_eq!(
+            Vec::<RdnValue>::try_from(RdnValue::Set(vec![])).unwrap_err(),
+            "expected Array, found Set"
+        );
+    }
+
+    // --- From tests ---
+
+    #[test]
+    fn from_numbers() {
+        let v: RdnValue = 42.into();
+        assert_eq!(v, RdnValue::Number(42.0));
+        assert_eq!(RdnValue::from(-7i64), RdnValue::Number(-7.0));
+        assert_eq!(RdnValue::from(1.5), RdnValue::Number(1.5));
+        // Past 2^53, integers round to the nearest f64.
+        assert_eq!(RdnValue::from(i64::MAX), RdnValue::Number(9_223_372_036_854_775_808.0));
+        assert_eq!(RdnValue::from((1i64 << 53) + 1), RdnValue::Number((1u64 << 53) as f64));
+    }
+
+    #[test]
+    fn from_bool_and_strings() {
+        assert_eq!(RdnValue::from(true), RdnValue::Bool(true));
+        assert_eq!(RdnValue::from("hi"), RdnValue::String("hi".to_string()));
+        assert_eq!(RdnValue::from("hi".to_string()), RdnValue::String("hi".to_string()));
+    }
+
+    #[test]
+    fn from_vecs() {
+        assert_eq!(RdnValue::from(vec![1u8, 2, 3]), RdnValue::Binary(vec![1, 2, 3]));
+        let items = vec![RdnValue::from(1), RdnValue::from("a")];
+        assert_eq!(RdnValue::from(items.clone()), RdnValue::Array(items));
+    }
+
+    // --- Object/Map retagging tests ---
+
+    #[test]
+    fn object_to_map_view() {
+        let obj = RdnValue::Object(vec![("b".to_string(), RdnValue::Null), ("a".to_string(), RdnValue::Bool(true))]);
+        assert_eq!(
+            obj.as_map_view(),
+            RdnValue::Map(vec![(s("b"), RdnValue::Null), (s("a"), RdnValue::Bool(true))])
+        );
+    }
+
+    #[test]
+    fn map_to_object_view() {
+        let map = RdnValue::Map(vec![(s("x"), RdnValue::Number(1.0))]);
+        assert_eq!(
+            map.as_object_view().unwrap(),
+            RdnValue::Object(vec![("x".to_string(), RdnValue::Number(1.0))])
+        );
+    }
+
+    #[test]
+    fn views_round_trip() {
+        let obj = RdnValue::Object(vec![("k".to_string(), RdnValue::Array(vec![]))]);
+        assert_eq!(obj.as_map_view().as_object_view().unwrap(), obj);
+    }
+
+    #[test]
+    fn object_view_rejects_non_string_keys() {
+        let map = RdnValue::Map(vec![(s("ok"), RdnValue::Null), (RdnValue::Number(1.0), RdnValue::Null)]);
+        assert_eq!(map.as_object_view().unwrap_err(), "Map key at index 1 is Number, not String");
+    }
+
+    #[test]
+    fn views_leave_other_values_unchanged() {
+        let arr = RdnValue::Array(vec![RdnValue::Null]);
+        assert_eq!(arr.as_map_view(), arr);
+        assert_eq!(arr.as_object_view().unwrap(), arr);
+        let map = RdnValue::Map(vec![(RdnValue::Null, RdnValue::Null)]);
+        assert_eq!(map.as_map_view(), map);
+    }
+
+    // --- Map pair conversion tests ---
+
+    #[test]
+    fn map_to_pairs_structure() {
+        let map = RdnValue::Map(vec![(RdnValue::Number(1.0), s("a")), (s("k"), RdnValue::Null)]);
+        assert_eq!(
+            map.map_to_pairs(),
+            Some(RdnValue::Array(vec![
+                RdnValue::Array(vec![RdnValue::Number(1.0), s("a")]),
+                RdnValue::Array(vec![s("k"), RdnValue::Null]),
+            ]))
+        );
+        assert_eq!(RdnValue::Object(vec![]).map_to_pairs(), None);
+    }
+
+    #[test]
+    fn pairs_round_trip() {
+        let map = RdnValue::Map(vec![(RdnValue::Array(vec![]), RdnValue::Bool(true))]);
+        assert_eq!(map.map_to_pairs().unwrap().pairs_to_map().unwrap(), map);
+    }
+
+    #[test]
+    fn pairs_to_map_rejects_bad_shapes() {
+        assert_eq!(RdnValue::Null.pairs_to_map().unwrap_err(), "expected Array, found Null");
+        let bad = RdnValue::Array(vec![RdnValue::Array(vec![s("k"), s("v")]), RdnValue::Array(vec![s("k")])]);
+        assert_eq!(bad.pairs_to_map().unwrap_err(), "element 1 is not a [key, value] pair");
+    }
+
+    // --- to_query_string tests ---
+
+    #[test]
+    fn query_string_flat_scalars() {
+        let v = obj(vec![
+            ("q", s("rust lang")),
+            ("page", RdnValue::Number(2.0)),
+            ("exact", RdnValue::Bool(false)),
+            ("cursor", RdnValue::Null),
+            ("id", RdnValue::BigInt(BigInt::new("9007199254740993").unwrap())),
+        ]);
+        assert_eq!(
+            v.to_query_string().unwrap(),
+            "q=rust%20lang&page=2&exact=false&cursor=&id=9007199254740993"
+        );
+    }
+
+    #[test]
+    fn query_string_bracket_notation() {
+        let v = obj(vec![
+            ("tags", RdnValue::Array(vec![s("a"), s("b&c")])),
+            ("f", obj(vec![("min", RdnValue::Number(0.5))])),
+        ]);
+        assert_eq!(v.to_query_string().unwrap(), "tags%5B0%5D=a&tags%5B1%5D=b%26c&f%5Bmin%5D=0.5");
+    }
+
+    #[test]
+    fn query_string_unsupported_is_none() {
+        assert_eq!(RdnValue::Array(vec![]).to_query_string(), None);
+        assert_eq!(obj(vec![("s", RdnValue::Set(vec![]))]).to_query_string(), None);
+        assert_eq!(obj(vec![]).to_query_string().unwrap(), "");
+    }
+}
@@ -0,0 +1,447 @@
+const MILLIS_PER_DAY: i64 = 86_400_000;
+
+/// The furthest a Date may be from the Unix epoch, in milliseconds: 100
+/// million days, as for a JavaScript `Date`.
+pub(crate) const MAX_DATE_MILLIS: i64 = 8_640_000_000_000_000;
+
+/// Returns `true` if `year` is a leap year in the proleptic Gregorian calendar.
+pub(crate) fn is_leap_year(year: i64) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+/// Returns the number of days in `month` (1-12) of `year`.
+pub(crate) fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Returns the number of days between 1970-01-01 and the given civil date.
+///
+/// Uses Howard Hinnant's `days_from_civil` algorithm, which is exact for the
+/// whole proleptic Gregorian calendar.
+pub(crate) fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y.rem_euclid(400);
+    let m = i64::from(month);
+    let mp = if m > 2 { m - 3 } else { m + 9 };
+    let doy = (153 * mp + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Returns the civil date `(year, month, day)` that is `days` after
+/// 1970-01-01; the inverse of [`days_from_civil`].
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Formats milliseconds since the Unix epoch as `YYYY-MM-DDTHH:mm:ss.sssZ`.
+///
+/// As in JavaScript's `toISOString`, a year outside 0000-9999 is written in
+/// the expanded form, a sign and six digits: `+010000` or `-000001`. Within
+/// [`MAX_DATE_MILLIS`] of the epoch six digits always suffice, and
+/// [`parse_iso_datetime`] reads the result back.
+pub(crate) fn format_iso_datetime(millis: i64) -> String {
+    let (year, month, day) = civil_from_days(millis.div_euclid(MILLIS_PER_DAY));
+    let ms = millis.rem_euclid(MILLIS_PER_DAY);
+    let (hours, minutes, seconds) = (ms / 3_600_000, ms / 60_000 % 60, ms / 1000 % 60);
+    let year = match year {
+        0..=9999 => format!("{year:04}"),
+        _ if year < 0 => format!("-{:06}", year.unsigned_abs()),
+        _ => format!("+{year:06}"),
+    };
+    format!("{year}-{month:02}-{day:02}T{hours:02}:{minutes:02}:{seconds:02}.{:03}Z", ms % 1000)
+}
+
+/// Formats a span of milliseconds as an ISO 8601 duration such as
+/// `P1DT2H3M4.5S`, with days of exactly 24 hours.
+///
+/// Zero components are left out, and a zero span is `PT0S`. A negative span
+/// gets a leading `-`, as in ISO 8601-2 and JavaScript's `Temporal`, which
+/// RDN's Duration literal does not accept.
+pub(crate) fn format_iso_duration(millis: i64) -> String {
+    let sign = if millis < 0 { "-" } else { "" };
+    let ms = millis.unsigned_abs();
+    let (days, hours, minutes) = (ms / 86_400_000, ms / 3_600_000 % 24, ms / 60_000 % 60);
+    let (seconds, fraction) = (ms / 1000 % 60, ms % 1000);
+    let mut out = format!("{sign}P");
+    if days > 0 {
+        out.push_str(&format!("{days}D"));
+    }
+    if ms.is_multiple_of(86_400_000) {
+        if days == 0 {
+            out.push_str("T0S");
+        }
+        return out;
+    }
+    out.push('T');
+    if hours > 0 {
+        out.push_str(&format!("{hours}H"));
+    }
+    if minutes > 0 {
+        out.push_str(&format!("{minutes}M"));
+    }
+    if fraction > 0 {
+        let digits = format!("{fraction:03}");
+        out.push_str(&format!("{seconds}.{}S", digits.trim_end_matches('0')));
+    } else if seconds > 0 {
+        out.push_str(&format!("{seconds}S"));
+    }
+    out
+}
+
+/// Reads exactly `N` ASCII digits from the front of `s`.
+fn take_digits<'a, const N: usize>(s: &'a str, what: &str) -> Result<(u32, &'a str), String> {
+    let digits = s.get(..N).filter(|d| d.bytes().all(|b| b.is_ascii_digit()));
+    match digits {
+        Some(d) => Ok((d.parse().unwrap_or(0), &s[N..])),
+        None => Err(format!("expected {N}-digit {what}")),
+    }
+}
+
+/// Strips the single character `ch` from the front of `s`.
+fn take_char<'a>(s: &'a str, ch: char, context: &str) -> Result<&'a str, String> {
+    s.strip_prefix(ch).ok_or_else(|| format!("expected '{ch}' {context}"))
+}
+
+/// Returns the ISO weekday (Monday = 1 through Sunday = 7) of a day number.
+fn iso_weekday(days: i64) -> i64 {
+    // 1970-01-01 was a Thursday.
+    (days + 3).rem_euclid(7) + 1
+}
+
+/// Returns the number of ISO weeks (52 or 53) in `year`.
+fn iso_weeks_in_year(year: i64) -> u32 {
+    let jan1 = iso_weekday(days_from_civil(year, 1, 1));
+    if jan1 == 4 || (jan1 == 3 && is_leap_year(year)) {
+        53
+    } else {
+        52
+    }
+}
+
+/// Parses the date part of an ISO 8601 string from the front of `s` and
+/// returns the day number relative to the Unix epoch.
+///
+/// The year is four digits, or in the expanded form a sign and six digits,
+/// such as `+010000` or `-000001`; `-000000` is not allowed. Three forms are
+/// accepted:
+///
+/// - calendar date `YYYY-MM-DD`
+/// - week date `YYYY-Www-D` (ISO week 01-53, weekday 1-7 with Monday = 1)
+/// - ordinal date `YYYY-DDD` (day of year 001-365, or 366 in leap years)
+fn parse_calendar_date(s: &str) -> Result<(i64, &str), String> {
+    let (year, rest) = match s.as_bytes().first() {
+        Some(&sign @ (b'+' | b'-')) => {
+            let (year, rest) = take_digits::<6>(&s[1..], "expanded year")?;
+            if sign == b'-' && year == 0 {
+                return Err("year -000000 is not allowed; write +000000".to_string());
+            }
+            (if sign == b'-' { -i64::from(year) } else { i64::from(year) }, rest)
+        }
+        _ => take_digits::<4>(s, "year").map(|(year, rest)| (i64::from(year), rest))?,
+    };
+    let rest = take_char(rest, '-', "after year")?;
+    if let Some(rest) = rest.strip_prefix('W') {
+        let (week, rest) = take_digits::<2>(rest, "week")?;
+        let rest = take_char(rest, '-', "after week")?;
+        let (weekday, rest) = take_digits::<1>(rest, "weekday")?;
+        let max_week = iso_weeks_in_year(year);
+        if week < 1 || week > max_week {
+            return Err(format!("week must be 01-{max_week} for {year:04}, got {week:02}"));
+        }
+        if !(1..=7).contains(&weekday) {
+            return Err(format!("weekday must be 1-7, got {weekday}"));
+        }
+        let jan4 = days_from_civil(year, 1, 4);
+        let week1_monday = jan4 - (iso_weekday(jan4) - 1);
+        return Ok((week1_monday + i64::from(week - 1) * 7 + i64::from(weekday - 1), rest));
+    }
+    if rest.len() >= 3 && rest.as_bytes()[..3].iter().all(u8::is_ascii_digit) {
+        let (ordinal, rest) = take_digits::<3>(rest, "day of year")?;
+        let max_ordinal = if is_leap_year(year) { 366 } else { 365 };
+        if ordinal < 1 || ordinal > max_ordinal {
+            return Err(format!("day of year must be 001-{max_ordinal} for {year:04}, got {ordinal:03}"));
+        }
+        return Ok((days_from_civil(year, 1, 1) + i64::from(ordinal - 1), rest));
+    }
+    let (month, rest) = take_digits::<2>(rest, "month")?;
+    let rest = take_char(rest, '-', "after month")?;
+    let (day, rest) = take_digits::<2>(rest, "day")?;
+    if !(1..=12).contains(&month) {
+        return Err(format!("month must be 01-12, got {month:02}"));
+    }
+    let max_day = days_in_month(year, month);
+    if day < 1 || day > max_day {
+        return Err(format!("day must be 01-{max_day} for {year:04}-{month:02}, got {day:02}"));
+    }
+    Ok((days_from_civil(year, month, day), rest))
+}
+
+/// Parses `HH:MM:SS[.fraction]` from the front of `s` and returns the time of
+/// day in milliseconds (the fraction may carry sub-millisecond precision).
+fn parse_time_of_day(s: &str) -> Result<(f64, &str), String> {
+    let (hours, rest) = take_digits::<2>(s, "hours")?;
+    let rest = take_char(rest, ':', "after hours")?;
+    let (minutes, rest) = take_digits::<2>(rest, "minutes")?;
+    let rest = take_char(rest, ':', "after minutes")?;
+    let (seconds, mut rest) = take_digits::<2>(rest, "seconds")?;
+    if hours > 23 {
+        return Err(format!("hours must be 00-23, got {hours:02}"));
+    }
+    if minutes > 59 {
+        return Err(format!("minutes must be 00-59, got {minutes:02}"));
+    }
+    if seconds > 59 {
+        return Err(format!("seconds must be 00-59, got {seconds:02}"));
+    }
+    let mut millis = f64::from(hours * 3_600_000 + minutes * 60_000 + seconds * 1000);
+    if let Some(after_dot) = rest.strip_prefix('.') {
+        let len = after_dot.bytes().take_while(u8::is_ascii_digit).count();
+        if len == 0 {
+            return Err("expected digits after '.' in seconds".to_string());
+        }
+        if len > 9 {
+            return Err("fractional seconds are limited to 9 digits".to_string());
+        }
+        let fraction: u32 = after_dot[..len].parse().unwrap_or(0);
+        millis += f64::from(fraction) * 1000.0 / 10f64.powi(len as i32);
+        rest = &after_dot[len..];
+    }
+    Ok((millis, rest))
+}
+
+/// Parses a UTC designator `Z` or a numeric offset `±HH:MM` and returns the
+/// offset east of UTC in milliseconds.
+fn parse_offset(s: &str) -> Result<(i64, &str), String> {
+    if let Some(rest) = s.strip_prefix('Z') {
+        return Ok((0, rest));
+    }
+    let (sign, rest) = match s.as_bytes().first() {
+        Some(b'+') => (1, &s[1..]),
+        Some(b'-') => (-1, &s[1..]),
+        _ => return Err("expected 'Z' or a '+HH:MM'/'-HH:MM' offset".to_string()),
+    };
+    let (hours, rest) = take_digits::<2>(rest, "offset hours")?;
+    let rest = take_char(rest, ':', "in offset")?;
+    let (minutes, rest) = take_digits::<2>(rest, "offset minutes")?;
+    if hours > 23 || minutes > 59 {
+        return Err(format!("offset out of range: {hours:02}:{minutes:02}"));
+    }
+    Ok((sign * i64::from(hours * 60 + minutes) * 60_000, rest))
+}
+
+/// Parses an ISO 8601 date or date-time (without a leading `@`) into
+/// milliseconds since the Unix epoch.
+///
+/// Accepted forms:
+///
+/// - `DATE` — midnight UTC
+/// - `DATETHH:MM:SS[.fraction](Z|±HH:MM)`
+///
+/// where `DATE` is a calendar date (`2024-01-15`), a week date (`2024-W03-1`),
+/// or an ordinal date (`2024-015`). Fields are range-checked, so `2024-02-30`
+/// is an error rather than rolling over into March. The whole string must be
+/// consumed.
+pub(crate) fn parse_iso_datetime(s: &str) -> Result<f64, String> {
+    let (days, rest) = parse_calendar_date(s)?;
+    let date_millis = (days * MILLIS_PER_DAY) as f64;
+    if rest.is_empty() {
+        return check_date_range(date_millis);
+    }
+    let rest = take_char(rest, 'T', "between date and time")?;
+    let (time_millis, rest) = parse_time_of_day(rest)?;
+    let (offset, rest) = parse_offset(rest)?;
+    if !rest.is_empty() {
+        return Err(format!("unexpected trailing characters in date: {rest}"));
+    }
+    check_date_range(date_millis + time_millis - offset as f64)
+}
+
+/// Returns `millis` if it is within [`MAX_DATE_MILLIS`] of the epoch.
+fn check_date_range(millis: f64) -> Result<f64, String> {
+    if millis.abs() > MAX_DATE_MILLIS as f64 {
+        return Err("date out of range".to_string());
+    }
+    Ok(millis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn days_from_civil_known_values() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 3, 1), 11_017);
+        assert_eq!(days_from_civil(1969, 12, 31), -1);
+    }
+
+    #[test]
+    fn civil_from_days_inverts_days_from_civil() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(11_017), (2000, 3, 1));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        for days in (-800_000..800_000).step_by(997) {
+            let (year, month, day) = civil_from_days(days);
+            assert_eq!(days_from_civil(year, month, day), days);
+        }
+    }
+
+    #[test]
+    fn format_iso_datetime_known_values() {
+        assert_eq!(format_iso_datetime(0), "1970-01-01T00:00:00.000Z");
+        assert_eq!(format_iso_datetime(1_705_314_600_123), "2024-01-15T10:30:00.123Z");
+        assert_eq!(format_iso_datetime(-1), "1969-12-31T23:59:59.999Z");
+        assert_eq!(format_iso_datetime(-62_167_219_200_000), "0000-01-01T00:00:00.000Z");
+        assert_eq!(format_iso_datetime(-62_167_219_200_001), "-000001-12-31T23:59:59.999Z");
+        assert_eq!(format_iso_datetime(253_402_300_799_999), "9999-12-31T23:59:59.999Z");
+        assert_eq!(format_iso_datetime(253_402_300_800_000), "+010000-01-01T00:00:00.000Z");
+        assert_eq!(format_iso_datetime(MAX_DATE_MILLIS), "+275760-09-13T00:00:00.000Z");
+        assert_eq!(format_iso_datetime(-MAX_DATE_MILLIS), "-271821-04-20T00:00:00.000Z");
+    }
+
+    #[test]
+    fn format_iso_datetime_round_trips() {
+        let boundaries = [
+            -MAX_DATE_MILLIS,
+            -62_198_755_200_000,
+            -62_167_219_200_001,
+            -62_167_219_200_000,
+            0,
+            253_402_300_799_999,
+            253_402_300_800_000,
+            MAX_DATE_MILLIS,
+        ];
+        for millis in boundaries {
+            assert_eq!(parse_iso_datetime(&format_iso_datetime(millis)).unwrap(), millis as f64, "{millis}");
+        }
+    }
+
+    #[test]
+    fn parse_expanded_years() {
+        assert_eq!(parse_iso_datetime("+002024-01-15").unwrap(), parse_iso_datetime("2024-01-15").unwrap());
+        assert_eq!(parse_iso_datetime("+000000-01-01").unwrap(), -62_167_219_200_000.0);
+        assert_eq!(parse_iso_datetime("-000001-365").unwrap(), parse_iso_datetime("-000001-12-31").unwrap());
+        assert!(parse_iso_datetime("-000000-01-01").unwrap_err().starts_with("year -000000"));
+        assert_eq!(parse_iso_datetime("+02024-01-15").unwrap_err(), "expected 6-digit expanded year");
+        assert_eq!(parse_iso_datetime("+275760-09-13T00:00:00.001Z").unwrap_err(), "date out of range");
+        assert_eq!(parse_iso_datetime("-271821-04-19T23:59:59.999Z").unwrap_err(), "date out of range");
+        assert_eq!(parse_iso_datetime("-271821-04-20").unwrap(), -MAX_DATE_MILLIS as f64);
+    }
+
+    #[test]
+    fn format_iso_duration_known_values() {
+        assert_eq!(format_iso_duration(0), "PT0S");
+        assert_eq!(format_iso_duration(1_500), "PT1.5S");
+        assert_eq!(format_iso_duration(-1_500), "-PT1.5S");
+        assert_eq!(format_iso_duration(50), "PT0.05S");
+        assert_eq!(format_iso_duration(3_600_000), "PT1H");
+        assert_eq!(format_iso_duration(86_400_000), "P1D");
+        assert_eq!(format_iso_duration(93_784_005), "P1DT2H3M4.005S");
+        assert_eq!(format_iso_duration(i64::MIN), "-P106751991167DT7H12M55.808S");
+    }
+
+    #[test]
+    fn parse_date_only_is_midnight_utc() {
+        assert_eq!(parse_iso_datetime("2024-01-15").unwrap(), 1_705_276_800_000.0);
+    }
+
+    #[test]
+    fn parse_full_utc() {
+        assert_eq!(parse_iso_datetime("2024-01-15T10:30:00.123Z").unwrap(), 1_705_314_600_123.0);
+        assert_eq!(parse_iso_datetime("2024-01-15T10:30:00Z").unwrap(), 1_705_314_600_000.0);
+    }
+
+    #[test]
+    fn parse_numeric_offset() {
+        let utc = parse_iso_datetime("2024-01-15T08:30:00Z").unwrap();
+        assert_eq!(parse_iso_datetime("2024-01-15T10:30:00+02:00").unwrap(), utc);
+        assert_eq!(parse_iso_datetime("2024-01-15T04:30:00-05:00").unwrap(), utc + 3_600_000.0);
+    }
+
+    #[test]
+    fn parse_sub_millisecond_fraction() {
+        assert_eq!(parse_iso_datetime("1970-01-01T00:00:00.0005Z").unwrap(), 0.5);
+        assert_eq!(parse_iso_datetime("1970-01-01T00:00:00.5Z").unwrap(), 500.0);
+    }
+
+    #[test]
+    fn parse_pre_epoch() {
+        assert_eq!(parse_iso_datetime("1969-12-31T23:59:59.000Z").unwrap(), -1000.0);
+    }
+
+    #[test]
+    fn reject_invalid_calendar_dates() {
+        assert!(parse_iso_datetime("2024-13-01").is_err());
+        assert!(parse_iso_datetime("2024-00-10").is_err());
+        assert!(parse_iso_datetime("2023-02-29").is_err());
+        assert!(parse_iso_datetime("2024-02-29").is_ok());
+        assert!(parse_iso_datetime("2024-04-31").is_err());
+    }
+
+    #[test]
+    fn parse_week_date() {
+        assert_eq!(parse_iso_datetime("2024-W03-1").unwrap(), parse_iso_datetime("2024-01-15").unwrap());
+        assert_eq!(parse_iso_datetime("2024-W03-7T12:00:00Z").unwrap(), parse_iso_datetime("2024-01-21T12:00:00Z").unwrap());
+    }
+
+    #[test]
+    fn parse_week_date_crossing_year_boundary() {
+        assert_eq!(parse_iso_datetime("2025-W01-1").unwrap(), parse_iso_datetime("2024-12-30").unwrap());
+        assert_eq!(parse_iso_datetime("2021-W01-1").unwrap(), parse_iso_datetime("2021-01-04").unwrap());
+    }
+
+    #[test]
+    fn week_53_only_in_long_years() {
+        assert_eq!(parse_iso_datetime("2020-W53-5").unwrap(), parse_iso_datetime("2021-01-01").unwrap());
+        assert!(parse_iso_datetime("2021-W53-1").is_err());
+        assert!(parse_iso_datetime("2024-W00-1").is_err());
+        assert!(parse_iso_datetime("2024-W01-8").is_err());
+        assert!(parse_iso_datetime("2024-W01-0").is_err());
+        assert!(parse_iso_datetime("2024-W1-1").is_err());
+    }
+
+    #[test]
+    fn parse_ordinal_date() {
+        assert_eq!(parse_iso_datetime("2024-045").unwrap(), parse_iso_datetime("2024-02-14").unwrap());
+        assert_eq!(parse_iso_datetime("2024-366").unwrap(), parse_iso_datetime("2024-12-31").unwrap());
+        assert_eq!(parse_iso_datetime("2023-001T00:00:01Z").unwrap(), parse_iso_datetime("2023-01-01T00:00:01Z").unwrap());
+    }
+
+    #[test]
+    fn reject_out_of_range_ordinal() {
+        assert!(parse_iso_datetime("2023-366").is_err());
+        assert!(parse_iso_datetime("2024-000").is_err());
+        assert!(parse_iso_datetime("2024-367").is_err());
+    }
+
+    #[test]
+    fn reject_malformed_times() {
+        assert!(parse_iso_datetime("2024-01-15T24:00:00Z").is_err());
+        assert!(parse_iso_datetime("2024-01-15T10:30:00").is_err());
+        assert!(parse_iso_datetime("2024-01-15T10:30Z").is_err());
+        assert!(parse_iso_datetime("2024-01-15T10:30:00.Z").is_err());
+        assert!(parse_iso_datetime("2024-01-15T10:30:00Zjunk").is_err());
+    }
+
+    #[test]
+    fn reject_unpadded_fields() {
+        assert!(parse_iso_datetime("2024-1-15").is_err());
+        assert!(parse_iso_datetime("24-01-15").is_err());
+    }
+}
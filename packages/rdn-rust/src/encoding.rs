@@ -0,0 +1,329 @@
+use std::fmt;
+
+/// Maps an RFC 4648 base64 alphabet character to its 6-bit value.
+fn base64_value(byte: u8) -> Option<u8> {
+    match byte {
+        b'A'..=b'Z' => Some(byte - b'A'),
+        b'a'..=b'z' => Some(byte - b'a' + 26),
+        b'0'..=b'9' => Some(byte - b'0' + 52),
+        b'+' => Some(62),
+        b'/' => Some(63),
+        _ => None,
+    }
+}
+
+/// Decodes standard (RFC 4648 §4) padded base64.
+///
+/// Validation is strict: the length must be a multiple of 4, `=` may only
+/// appear as one or two trailing characters, whitespace is not skipped, and
+/// non-zero padding bits are rejected (RFC 4648 §3.5).
+pub(crate) fn decode_base64(input: &str) -> Result<Vec<u8>, String> {
+    let bytes = input.as_bytes();
+    if !bytes.len().is_multiple_of(4) {
+        return Err("invalid base64: length must be a multiple of 4".to_string());
+    }
+    let padding = bytes.iter().rev().take_while(|&&b| b == b'=').count();
+    if padding > 2 {
+        return Err("invalid base64: too much padding".to_string());
+    }
+    let data = &bytes[..bytes.len() - padding];
+    let mut out = Vec::with_capacity(bytes.len() / 4 * 3);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for &byte in data {
+        let value = match base64_value(byte) {
+            Some(v) => v,
+            None if byte == b'=' => return Err("invalid base64: padding before end of data".to_string()),
+            None => return Err(format!("invalid base64 character: {:?}", byte as char)),
+        };
+        buffer = (buffer << 6) | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    if buffer != 0 {
+        return Err("invalid base64: non-zero padding bits".to_string());
+    }
+    Ok(out)
+}
+
+/// Maps an RFC 4648 base32 alphabet character to its 5-bit value.
+fn base32_value(byte: u8) -> Option<u8> {
+    match byte {
+        b'A'..=b'Z' => Some(byte - b'A'),
+        b'2'..=b'7' => Some(byte - b'2' + 26),
+        _ => None,
+    }
+}
+
+/// Decodes standard (RFC 4648 §6) padded base32, not the "extended hex"
+/// alphabet of §7.
+///
+/// Validation is as strict as [`decode_base64`]'s: the length must be a
+/// multiple of 8, `=` may only appear as 1, 3, 4 or 6 trailing characters,
+/// letters must be uppercase, and non-zero padding bits are rejected.
+pub(crate) fn decode_base32(input: &str) -> Result<Vec<u8>, String> {
+    let bytes = input.as_bytes();
+    if !bytes.len().is_multiple_of(8) {
+        return Err("invalid base32: length must be a multiple of 8".to_string());
+    }
+    let padding = bytes.iter().rev().take_while(|&&b| b == b'=').count();
+    if !matches!(padding, 0 | 1 | 3 | 4 | 6) {
+        return Err("invalid base32: wrong amount of padding".to_string());
+    }
+    let data = &bytes[..bytes.len() - padding];
+    let mut out = Vec::with_capacity(bytes.len() / 8 * 5);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for &byte in data {
+        let value = match base32_value(byte) {
+            Some(v) => v,
+            None if byte == b'=' => return Err("invalid base32: padding before end of data".to_string()),
+            None => return Err(format!("invalid base32 character: {:?}", byte as char)),
+        };
+        buffer = (buffer << 5) | u32::from(value);
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    if buffer != 0 {
+        return Err("invalid base32: non-zero padding bits".to_string());
+    }
+    Ok(out)
+}
+
+/// Decodes hexadecimal, two digits per byte, in either case.
+pub(crate) fn decode_hex(input: &str) -> Result<Vec<u8>, String> {
+    if !input.len().is_multiple_of(2) {
+        return Err("invalid hex: odd number of digits".to_string());
+    }
+    let mut out = Vec::with_capacity(input.len() / 2);
+    let mut high = None;
+    for ch in input.chars() {
+        let Some(digit) = ch.to_digit(16) else {
+            return Err(format!("invalid hex character: {ch:?}"));
+        };
+        match high.take() {
+            None => high = Some(digit),
+            Some(h) => out.push((h << 4 | digit) as u8),
+        }
+    }
+    Ok(out)
+}
+
+/// Encodes `bytes` as standard (RFC 4648 §4) padded base64.
+pub(crate) fn encode_base64(bytes: &[u8], out: &mut impl fmt::Write) -> fmt::Result {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |acc, (i, &b)| acc | u32::from(b) << (16 - 8 * i));
+        // A chunk of n bytes fills n + 1 characters; the rest are padding.
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.write_char(ALPHABET[(group >> (18 - 6 * i) & 0x3F) as usize] as char)?;
+            } else {
+                out.write_char('=')?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Encodes `bytes` as standard (RFC 4648 §6) padded base32.
+pub(crate) fn encode_base32(bytes: &[u8], out: &mut impl fmt::Write) -> fmt::Result {
+    const ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+    for chunk in bytes.chunks(5) {
+        let group = chunk.iter().enumerate().fold(0u64, |acc, (i, &b)| acc | u64::from(b) << (32 - 8 * i));
+        // A chunk of n bytes fills ceil(8n / 5) characters; the rest are padding.
+        let filled = (chunk.len() * 8).div_ceil(5);
+        for i in 0..8 {
+            if i < filled {
+                out.write_char(ALPHABET[(group >> (35 - 5 * i) & 0x1F) as usize] as char)?;
+            } else {
+                out.write_char('=')?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Encodes `bytes` as lowercase hexadecimal, two digits per byte.
+pub(crate) fn encode_hex(bytes: &[u8], out: &mut impl fmt::Write) -> fmt::Result {
+    bytes.iter().try_for_each(|byte| write!(out, "{byte:02x}"))
+}
+
+/// Percent-encodes `input` for use in a URL query component.
+///
+/// Every byte of the UTF-8 encoding is written as `%XX` (uppercase hex)
+/// except the RFC 3986 unreserved characters `A-Z a-z 0-9 - . _ ~`. Spaces
+/// become `%20`, not `+`.
+pub(crate) fn percent_encode(input: &str, out: &mut String) {
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+    for &byte in input.as_bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            out.push(byte as char);
+        } else {
+            out.push('%');
+            out.push(HEX[usize::from(byte >> 4)] as char);
+            out.push(HEX[usize::from(byte & 0xF)] as char);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_base64_known_values() {
+        assert_eq!(decode_base64("").unwrap(), b"");
+        assert_eq!(decode_base64("SGVsbG8=").unwrap(), b"Hello");
+        assert_eq!(decode_base64("SGk=").unwrap(), b"Hi");
+        assert_eq!(decode_base64("SGV5").unwrap(), b"Hey");
+        assert_eq!(decode_base64("+/8=").unwrap(), [0xfb, 0xff]);
+    }
+
+    #[test]
+    fn decode_base64_rejects_bad_length() {
+        assert!(decode_base64("SGVsbG8").is_err());
+        assert!(decode_base64("S").is_err());
+    }
+
+    #[test]
+    fn decode_base64_rejects_bad_characters() {
+        assert!(decode_base64("not base64!!").is_err());
+        assert!(decode_base64("SGVs bG8=").is_err());
+    }
+
+    #[test]
+    fn decode_base64_rejects_misplaced_padding() {
+        assert!(decode_base64("SG=sbG8=").is_err());
+        assert!(decode_base64("S===").is_err());
+    }
+
+    #[test]
+    fn decode_base64_rejects_non_zero_padding_bits() {
+        assert!(decode_base64("SGk=").is_ok());
+        assert!(decode_base64("SGl=").is_err());
+        assert!(decode_base64("SR==").is_err());
+    }
+
+    // --- encode_base64 tests ---
+
+    fn base64(bytes: &[u8]) -> String {
+        let mut out = String::new();
+        encode_base64(bytes, &mut out).unwrap();
+        out
+    }
+
+    #[test]
+    fn encode_base64_known_values() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"H"), "SA==");
+        assert_eq!(base64(b"Hi"), "SGk=");
+        assert_eq!(base64(b"Hello"), "SGVsbG8=");
+        assert_eq!(base64(&[0xfb, 0xff, 0xbf]), "+/+/");
+    }
+
+    #[test]
+    fn encode_base64_round_trips() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        assert_eq!(decode_base64(&base64(&bytes)).unwrap(), bytes);
+    }
+
+    // --- base32 tests ---
+
+    fn base32(bytes: &[u8]) -> String {
+        let mut out = String::new();
+        encode_base32(bytes, &mut out).unwrap();
+        out
+    }
+
+    #[test]
+    fn base32_rfc_4648_vectors() {
+        let vectors = [
+            ("", ""),
+            ("f", "MY======"),
+            ("fo", "MZXQ===="),
+            ("foo", "MZXW6==="),
+            ("foob", "MZXW6YQ="),
+            ("fooba", "MZXW6YTB"),
+            ("foobar", "MZXW6YTBOI======"),
+        ];
+        for (plain, encoded) in vectors {
+            assert_eq!(base32(plain.as_bytes()), encoded);
+            assert_eq!(decode_base32(encoded).unwrap(), plain.as_bytes());
+        }
+    }
+
+    #[test]
+    fn base32_round_trips() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        assert_eq!(decode_base32(&base32(&bytes)).unwrap(), bytes);
+    }
+
+    #[test]
+    fn decode_base32_rejects_bad_input() {
+        assert_eq!(decode_base32("MY=====").unwrap_err(), "invalid base32: length must be a multiple of 8");
+        assert_eq!(decode_base32("MZXW6Y==").unwrap_err(), "invalid base32: wrong amount of padding");
+        assert_eq!(decode_base32("M=======").unwrap_err(), "invalid base32: wrong amount of padding");
+        assert_eq!(decode_base32("MY=X====").unwrap_err(), "invalid base32: padding before end of data");
+        assert_eq!(decode_base32("my======").unwrap_err(), "invalid base32 character: 'm'");
+        assert_eq!(decode_base32("M1======").unwrap_err(), "invalid base32 character: '1'");
+        assert_eq!(decode_base32("MZ======").unwrap_err(), "invalid base32: non-zero padding bits");
+    }
+
+    // --- decode_hex tests ---
+
+    #[test]
+    fn decode_hex_known_values() {
+        assert_eq!(decode_hex("").unwrap(), b"");
+        assert_eq!(decode_hex("48656C6C6F").unwrap(), b"Hello");
+        assert_eq!(decode_hex("ff00Ab").unwrap(), [0xff, 0x00, 0xab]);
+    }
+
+    #[test]
+    fn decode_hex_rejects_bad_input() {
+        assert_eq!(decode_hex("abc").unwrap_err(), "invalid hex: odd number of digits");
+        assert_eq!(decode_hex("0g").unwrap_err(), "invalid hex character: 'g'");
+        assert!(decode_hex("\u{e9}").is_err());
+    }
+
+    #[test]
+    fn encode_hex_round_trips() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        let mut out = String::new();
+        encode_hex(&bytes, &mut out).unwrap();
+        assert_eq!(&out[..8], "00010203");
+        assert_eq!(&out[out.len() - 4..], "feff");
+        assert_eq!(decode_hex(&out).unwrap(), bytes);
+    }
+
+    // --- percent_encode tests ---
+
+    fn encoded(input: &str) -> String {
+        let mut out = String::new();
+        percent_encode(input, &mut out);
+        out
+    }
+
+    #[test]
+    fn percent_encode_keeps_unreserved() {
+        assert_eq!(encoded("AZaz09-._~"), "AZaz09-._~");
+    }
+
+    #[test]
+    fn percent_encode_reserved_and_space() {
+        assert_eq!(encoded("a b&c=d/e?[0]"), "a%20b%26c%3Dd%2Fe%3F%5B0%5D");
+    }
+
+    #[test]
+    fn percent_encode_utf8_bytes() {
+        assert_eq!(encoded("\u{e9}"), "%C3%A9");
+    }
+}
@@ -0,0 +1,305 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Write};
+
+/// Writes a finite `n` as the shortest decimal that parses back to the same
+/// `f64`, laid out as JavaScript's `Number.prototype.toString` does: plain
+/// notation for `1e-7 <= |n| < 1e21`, otherwise one digit before the point
+/// and a signed exponent (`1e+21`, `5e-324`). Unlike JavaScript, `-0` keeps
+/// its sign.
+///
+/// The digits come from the exact Burger–Dybvig free-format algorithm with
+/// ties broken towards the even digit, so the output is fully determined by
+/// the bits of `n` and does not depend on `std`'s float formatting.
+pub(crate) fn write_shortest(out: &mut impl Write, n: f64) -> fmt::Result {
+    debug_assert!(n.is_finite());
+    if n.is_sign_negative() {
+        out.write_char('-')?;
+    }
+    let n = n.abs();
+    if n == 0.0 {
+        return out.write_char('0');
+    }
+    // Every integer up to 2^53 is exact and is its own shortest form.
+    if n.fract() == 0.0 && n <= 9_007_199_254_740_992.0 {
+        return write!(out, "{}", n as u64);
+    }
+    let (digits, point) = shortest_digits(n);
+    write_digits(out, &digits, point)
+}
+
+/// Lays out `0.d1d2d3... * 10^point` following ECMA-262 `Number::toString`.
+fn write_digits(out: &mut impl Write, digits: &[u8], point: i32) -> fmt::Result {
+    let len = digits.len() as i32;
+    let text = |d: &[u8]| -> String { d.iter().map(|&d| char::from(b'0' + d)).collect() };
+    if len <= point && point <= 21 {
+        out.write_str(&text(digits))?;
+        (len..point).try_for_each(|_| out.write_char('0'))
+    } else if 0 < point && point <= 21 {
+        let (int, frac) = digits.split_at(point as usize);
+        write!(out, "{}.{}", text(int), text(frac))
+    } else if -6 < point && point <= 0 {
+        out.write_str("0.")?;
+        (point..0).try_for_each(|_| out.write_char('0'))?;
+        out.write_str(&text(digits))
+    } else {
+        out.write_str(&text(&digits[..1]))?;
+        if len > 1 {
+            write!(out, ".{}", text(&digits[1..]))?;
+        }
+        let exponent = point - 1;
+        write!(out, "e{}{}", if exponent < 0 { '-' } else { '+' }, exponent.abs())
+    }
+}
+
+/// Returns the shortest digit string `d` and decimal point position `k` such
+/// that `0.d * 10^k` rounds to `v` (positive, finite, non-zero).
+fn shortest_digits(v: f64) -> (Vec<u8>, i32) {
+    const HIDDEN_BIT: u64 = 1 << 52;
+    const MIN_EXPONENT: i32 = -1074;
+
+    let bits = v.to_bits();
+    let biased = ((bits >> 52) & 0x7ff) as i32;
+    let fraction = bits & (HIDDEN_BIT - 1);
+    let (f, e) = if biased == 0 { (fraction, MIN_EXPONENT) } else { (fraction | HIDDEN_BIT, biased - 1075) };
+    // Under round-half-even, the halfway points between `v` and its
+    // neighbours still read back as `v` when its significand is even.
+    let inclusive = f % 2 == 0;
+    // The gap below a power of two is half the gap above it.
+    let uneven_gaps = f == HIDDEN_BIT && e > MIN_EXPONENT;
+
+    // v = r / s; the halfway points to the neighbours are (r ± m) / s.
+    let (mut r, mut s, mut m_plus, mut m_minus);
+    if e >= 0 {
+        let be = Big::from_u64(1).shl(e as u32);
+        if uneven_gaps {
+            r = Big::from_u64(f).shl(e as u32 + 2);
+            s = Big::from_u64(4);
+            m_plus = be.clone().shl(1);
+            m_minus = be;
+        } else {
+            r = Big::from_u64(f).shl(e as u32 + 1);
+            s = Big::from_u64(2);
+            m_plus = be.clone();
+            m_minus = be;
+        }
+    } else if uneven_gaps {
+        r = Big::from_u64(f).shl(2);
+        s = Big::from_u64(1).shl((2 - e) as u32);
+        m_plus = Big::from_u64(2);
+        m_minus = Big::from_u64(1);
+    } else {
+        r = Big::from_u64(f).shl(1);
+        s = Big::from_u64(1).shl((1 - e) as u32);
+        m_plus = Big::from_u64(1);
+        m_minus = Big::from_u64(1);
+    }
+
+    // An estimate of ceil(log10(v)) that is never too large and at most one
+    // too small, corrected below.
+    let top_bit = e + 63 - f.leading_zeros() as i32;
+    let mut k = (f64::from(top_bit) * std::f64::consts::LOG10_2 - 1e-10).ceil() as i32;
+    if k >= 0 {
+        s = s.mul_pow10(k as u32);
+    } else {
+        r = r.mul_pow10((-k) as u32);
+        m_plus = m_plus.mul_pow10((-k) as u32);
+        m_minus = m_minus.mul_pow10((-k) as u32);
+    }
+    if reaches(&r.add(&m_plus), &s, inclusive) {
+        s = s.mul_small(10);
+        k += 1;
+    }
+
+    let mut digits = Vec::with_capacity(17);
+    loop {
+        r = r.mul_small(10);
+        m_plus = m_plus.mul_small(10);
+        m_minus = m_minus.mul_small(10);
+        let mut digit = 0u8;
+        while r.cmp(&s) != Ordering::Less {
+            r.sub_assign(&s);
+            digit += 1;
+        }
+        let low = if inclusive { r.cmp(&m_minus) != Ordering::Greater } else { r.cmp(&m_minus) == Ordering::Less };
+        let high = reaches(&r.add(&m_plus), &s, inclusive);
+        if !low && !high {
+            digits.push(digit);
+            continue;
+        }
+        let round_up = match (low, high) {
+            (true, false) => false,
+            (false, true) => true,
+            _ => match r.shl(1).cmp(&s) {
+                Ordering::Less => false,
+                Ordering::Greater => true,
+                Ordering::Equal => digit % 2 == 1,
+            },
+        };
+        digits.push(digit + u8::from(round_up));
+        return (digits, k);
+    }
+}
+
+/// Whether `x >= y` (or `x > y` when the boundary is exclusive).
+fn reaches(x: &Big, y: &Big, inclusive: bool) -> bool {
+    match x.cmp(y) {
+        Ordering::Greater => true,
+        Ordering::Equal => inclusive,
+        Ordering::Less => false,
+    }
+}
+
+/// A minimal unsigned big integer: little-endian base-2^32 limbs without
+/// trailing zero limbs.
+#[derive(Clone)]
+struct Big(Vec<u32>);
+
+impl Big {
+    fn from_u64(v: u64) -> Big {
+        let mut big = Big(vec![v as u32, (v >> 32) as u32]);
+        big.trim();
+        big
+    }
+
+    fn trim(&mut self) {
+        while self.0.last() == Some(&0) {
+            self.0.pop();
+        }
+    }
+
+    fn shl(mut self, bits: u32) -> Big {
+        let (limbs, bits) = ((bits / 32) as usize, bits % 32);
+        if bits > 0 {
+            let mut carry = 0;
+            for limb in &mut self.0 {
+                let next = *limb >> (32 - bits);
+                *limb = (*limb << bits) | carry;
+                carry = next;
+            }
+            self.0.push(carry);
+        }
+        self.0.splice(0..0, std::iter::repeat_n(0, limbs));
+        self.trim();
+        self
+    }
+
+    fn mul_small(mut self, factor: u32) -> Big {
+        let mut carry = 0u64;
+        for limb in &mut self.0 {
+            let product = u64::from(*limb) * u64::from(factor) + carry;
+            *limb = product as u32;
+            carry = product >> 32;
+        }
+        self.0.push(carry as u32);
+        self.trim();
+        self
+    }
+
+    fn mul_pow10(mut self, mut exponent: u32) -> Big {
+        while exponent >= 9 {
+            self = self.mul_small(1_000_000_000);
+            exponent -= 9;
+        }
+        self.mul_small(10u32.pow(exponent))
+    }
+
+    fn add(&self, other: &Big) -> Big {
+        let (long, short) = if self.0.len() >= other.0.len() { (self, other) } else { (other, self) };
+        let mut sum = Vec::with_capacity(long.0.len() + 1);
+        let mut carry = 0u64;
+        for (i, &limb) in long.0.iter().enumerate() {
+            let total = u64::from(limb) + u64::from(short.0.get(i).copied().unwrap_or(0)) + carry;
+            sum.push(total as u32);
+            carry = total >> 32;
+        }
+        sum.push(carry as u32);
+        let mut big = Big(sum);
+        big.trim();
+        big
+    }
+
+    /// `self -= other`; requires `self >= other`.
+    fn sub_assign(&mut self, other: &Big) {
+        let mut borrow = 0i64;
+        for (i, limb) in self.0.iter_mut().enumerate() {
+            let diff = i64::from(*limb) - i64::from(other.0.get(i).copied().unwrap_or(0)) - borrow;
+            *limb = diff.rem_euclid(1 << 32) as u32;
+            borrow = i64::from(diff < 0);
+        }
+        self.trim();
+    }
+
+    fn cmp(&self, other: &Big) -> Ordering {
+        self.0.len().cmp(&other.0.len()).then_with(|| self.0.iter().rev().cmp(other.0.iter().rev()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shortest(n: f64) -> String {
+        let mut out = String::new();
+        write_shortest(&mut out, n).unwrap();
+        out
+    }
+
+    // --- Frozen output ---
+
+    #[test]
+    fn frozen_vectors() {
+        // Expected strings match JavaScript's `String(n)` apart from `-0`,
+        // and must never change: canonical output depends on them.
+        let cases: &[(f64, &str)] = &[
+            (0.0, "0"),
+            (-0.0, "-0"),
+            (1.0, "1"),
+            (-17.0, "-17"),
+            (0.1 + 0.2, "0.30000000000000004"),
+            (1.0 / 3.0, "0.3333333333333333"),
+            (123.456, "123.456"),
+            (4.35, "4.35"),
+            (9_007_199_254_740_992.0, "9007199254740992"),
+            (1_152_921_504_606_846_976.0, "1152921504606847000"),
+            (1e20, "100000000000000000000"),
+            (123_456_789_012_345_680_000.0, "123456789012345680000"),
+            (1e21, "1e+21"),
+            (1.5e21, "1.5e+21"),
+            (1e23, "1e+23"),
+            (1e300, "1e+300"),
+            (f64::MAX, "1.7976931348623157e+308"),
+            (1e-6, "0.000001"),
+            (0.000_001_234, "0.000001234"),
+            (1e-7, "1e-7"),
+            (-1.5e-10, "-1.5e-10"),
+            (9.536_743_164_062_5e-7, "9.5367431640625e-7"),
+            (f64::MIN_POSITIVE, "2.2250738585072014e-308"),
+            (f64::from_bits(0x000f_ffff_ffff_ffff), "2.225073858507201e-308"),
+            (f64::from_bits(2), "1e-323"),
+            (5e-324, "5e-324"),
+            (-5e-324, "-5e-324"),
+        ];
+        for &(n, expected) in cases {
+            assert_eq!(shortest(n), expected, "formatting {n:e}");
+        }
+    }
+
+    // --- Round trips ---
+
+    #[test]
+    fn output_is_shortest_and_round_trips() {
+        let mut state = 0x853c_49e6_748f_ea9b_u64;
+        for _ in 0..10_000 {
+            state = state.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1_442_695_040_888_963_407);
+            let n = f64::from_bits(state);
+            if !n.is_finite() {
+                continue;
+            }
+            let text = shortest(n);
+            assert_eq!(text.parse::<f64>().unwrap().to_bits(), n.to_bits(), "{text}");
+            let significant = |s: &str| s.split('e').next().unwrap().replace(['-', '.'], "").trim_matches('0').len();
+            let std_text = format!("{n:e}");
+            assert!(significant(&text) <= significant(&std_text), "{text} vs {std_text}");
+        }
+    }
+}
@@ -0,0 +1,513 @@
+use crate::parser::{parse_with, ParseError, ParseOptions};
+use crate::types::*;
+use std::io::{ErrorKind, Read};
+
+/// Parse RDN from a reader, such as a file or socket, without first reading it
+/// into a `String`.
+///
+/// The input is read in chunks through an [`IncrementalParser`], so error
+/// positions are byte offsets from the start of the stream, just as
+/// [`parse`](crate::parse) reports them. The reader is read to the end, and
+/// only whitespace may follow the value.
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] if the input is malformed or not valid UTF-8. An
+/// I/O error is reported as a `ParseError` at the offset reached.
+///
+/// # Examples
+///
+/// ```
+/// use rdn::{parse_from_reader, RdnValue};
+///
+/// let value = parse_from_reader(&b"[1, 2]"[..]).unwrap();
+/// assert_eq!(value, RdnValue::Array(vec![RdnValue::Number(1.0), RdnValue::Number(2.0)]));
+/// ```
+pub fn parse_from_reader<R: Read>(mut reader: R) -> Result<RdnValue, ParseError> {
+    let mut parser = IncrementalParser::new();
+    let mut chunk = [0; 8192];
+    let mut value = None;
+    loop {
+        let len = match reader.read(&mut chunk) {
+            Ok(0) => break,
+            Ok(len) => len,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => {
+                let offset = parser.buffer.len();
+                return Err(ParseError::new(&parser.buffer, offset, &format!("I/O error: {e}")));
+            }
+        };
+        if let Some(v) = parser.feed(&chunk[..len])? {
+            value = Some(v);
+        }
+    }
+    // finish() returns the value unless feed() already has, so one of the
+    // two is always set.
+    let rest = parser.finish()?;
+    Ok(value.or(rest).unwrap_or(RdnValue::Null))
+}
+
+/// Where the scanner is within the document's text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum Scan {
+    /// Outside any string or regular expression.
+    #[default]
+    Code,
+    /// Inside a string closed by `quote`, including the payload of `b"..."`
+    /// and `x"..."`.
+    String { quote: u8, escaped: bool },
+    /// Inside `/.../`, before the flags. As in the parser, a `/` inside a
+    /// `[...]` character class does not end the pattern.
+    RegExp { escaped: bool, in_class: bool },
+    /// After a `/` in code while comments are allowed, where the next byte
+    /// decides between a comment and a RegExp.
+    Slash,
+    /// Inside `// ...`.
+    LineComment,
+    /// Inside `/* ... */`, which started at byte `start`.
+    BlockComment { start: usize, star: bool },
+}
+
+/// A push parser for input that arrives in pieces, such as from a socket in
+/// an async runtime.
+///
+/// Bytes are buffered across calls to [`IncrementalParser::feed`] until a
+/// complete top-level value has arrived, which is then parsed in one go.
+/// Splitting the input anywhere is fine, including inside a token or a
+/// multi-byte UTF-8 character. Error positions are byte offsets from the
+/// start of the stream.
+///
+/// The end of a document is recognized from the brackets and quotes that
+/// close it. A bare top-level scalar such as `42` or `@2024-01-15` has no
+/// closer, so it is only complete once whitespace follows it or
+/// [`IncrementalParser::finish`] is called. With
+/// [`ParseOptions::allow_comments`], a comment ends it too.
+///
+/// ```
+/// use rdn::{IncrementalParser, RdnValue};
+///
+/// let mut parser = IncrementalParser::new();
+/// assert_eq!(parser.feed(br#"{"ok": tr"#).unwrap(), None);
+/// let value = parser.feed(b"ue}").unwrap().unwrap();
+/// assert_eq!(value, RdnValue::Object(vec![("ok".to_string(), RdnValue::Bool(true))]));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct IncrementalParser {
+    options: ParseOptions,
+    buffer: Vec<u8>,
+    /// How much of `buffer` the scanner has looked at.
+    scanned: usize,
+    state: Scan,
+    depth: usize,
+    /// Whether the scanner has seen the first byte of the value.
+    started: bool,
+    /// Whether the last byte scanned closed a bracket or quote at depth 0.
+    closed: bool,
+    /// Whether the value has been returned. From then on the scanner only
+    /// checks that whitespace and comments follow.
+    done: bool,
+}
+
+impl IncrementalParser {
+    /// Creates a parser for the syntax accepted by [`parse`](crate::parse).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a parser for the syntax accepted by
+    /// [`parse_with`](crate::parse_with) with `options`.
+    pub fn with_options(options: ParseOptions) -> Self {
+        IncrementalParser { options, ..Self::default() }
+    }
+
+    /// Appends `bytes` to the input, returning the value once a complete
+    /// top-level document has arrived and `None` while more is needed.
+    ///
+    /// The value is returned only once. Whitespace may follow it; anything
+    /// else is an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns the parse error once the document is complete but malformed,
+    /// or if it is not valid UTF-8.
+    pub fn feed(&mut self, bytes: &[u8]) -> Result<Option<RdnValue>, ParseError> {
+        self.buffer.extend_from_slice(bytes);
+        if self.done {
+            return self.scan_trailing().map(|()| None);
+        }
+        let Some(end) = self.scan() else {
+            return Ok(None);
+        };
+        let rest = self.buffer.split_off(end);
+        let value = self.parse_buffer()?;
+        self.buffer.extend_from_slice(&rest);
+        self.scanned = end;
+        self.state = Scan::Code;
+        self.scan_trailing()?;
+        Ok(Some(value))
+    }
+
+    /// Signals the end of the input, returning the value if
+    /// [`IncrementalParser::feed`] has not already done so.
+    ///
+    /// # Errors
+    ///
+    /// Returns the parse error if the buffered input is incomplete or
+    /// malformed.
+    pub fn finish(mut self) -> Result<Option<RdnValue>, ParseError> {
+        if self.done {
+            return match self.state {
+                Scan::Slash => Err(self.trailing_data(self.buffer.len() - 1)),
+                Scan::BlockComment { start, .. } => {
+                    Err(ParseError::new(&self.buffer, start, "Unterminated comment"))
+                }
+                _ => Ok(None),
+            };
+        }
+        self.parse_buffer().map(Some)
+    }
+
+    /// Advances the scanner over the unscanned input, returning the length
+    /// of the document once its end is found.
+    fn scan(&mut self) -> Option<usize> {
+        while self.scanned < self.buffer.len() {
+            let b = self.buffer[self.scanned];
+            self.scanned += 1;
+            match self.state {
+                Scan::String { quote, escaped: true } => self.state = Scan::String { quote, escaped: false },
+                Scan::RegExp { escaped: true, in_class } => self.state = Scan::RegExp { escaped: false, in_class },
+                Scan::String { quote, escaped: false } => match b {
+                    b'\\' => self.state = Scan::String { quote, escaped: true },
+                    _ if b == quote => {
+                        self.state = Scan::Code;
+                        self.closed = self.depth == 0;
+                    }
+                    _ => {}
+                },
+                Scan::RegExp { escaped: false, in_class } => match b {
+                    b'\\' => self.state = Scan::RegExp { escaped: true, in_class },
+                    // The flags follow, so a top-level RegExp ends like a scalar.
+                    b'/' if !in_class => self.state = Scan::Code,
+                    b'[' => self.state = Scan::RegExp { escaped: false, in_class: true },
+                    b']' => self.state = Scan::RegExp { escaped: false, in_class: false },
+                    _ => {}
+                },
+                Scan::Code => {
+                    if is_ws(b) {
+                        if self.started && self.depth == 0 {
+                            return Some(self.scanned - 1);
+                        }
+                        continue;
+                    }
+                    if b == b'/' && self.options.allow_comments {
+                        self.state = Scan::Slash;
+                        continue;
+                    }
+                    if let Some(end) = self.scan_code(b) {
+                        return Some(end);
+                    }
+                }
+                Scan::Slash => match b {
+                    b'/' | b'*' => {
+                        // A comment ends a top-level scalar, like whitespace.
+                        if self.started && self.depth == 0 {
+                            return Some(self.scanned - 2);
+                        }
+                        self.state = self.comment_state(b);
+                    }
+                    _ => {
+                        // A RegExp: scan this byte again as part of it.
+                        self.state = Scan::Code;
+                        self.scanned -= 1;
+                        if let Some(end) = self.scan_code(b'/') {
+                            return Some(end);
+                        }
+                    }
+                },
+                Scan::LineComment | Scan::BlockComment { .. } => self.scan_comment(b),
+            }
+        }
+        (self.closed && self.state == Scan::Code).then_some(self.scanned)
+    }
+
+    /// Scans a byte of code other than whitespace, returning the length of
+    /// the document if it is known to have ended.
+    fn scan_code(&mut self, b: u8) -> Option<usize> {
+        if self.closed {
+            // Something follows a closed document; let the parser report it.
+            return Some(self.scanned);
+        }
+        self.started = true;
+        match b {
+            b'"' => self.state = Scan::String { quote: b, escaped: false },
+            b'\'' if self.options.allow_single_quotes => self.state = Scan::String { quote: b, escaped: false },
+            b'/' => self.state = Scan::RegExp { escaped: false, in_class: false },
+            b'[' | b'{' | b'(' => self.depth += 1,
+            b']' | b'}' | b')' => {
+                if self.depth == 0 {
+                    return Some(self.scanned);
+                }
+                self.depth -= 1;
+                self.closed = self.depth == 0;
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// The state for a comment opened by `/` and then `b`.
+    fn comment_state(&self, b: u8) -> Scan {
+        match b {
+            b'/' => Scan::LineComment,
+            _ => Scan::BlockComment { start: self.scanned - 2, star: false },
+        }
+    }
+
+    fn scan_comment(&mut self, b: u8) {
+        self.state = match (self.state, b) {
+            (Scan::LineComment, b'\n') => Scan::Code,
+            (Scan::BlockComment { star: true, .. }, b'/') => Scan::Code,
+            (Scan::BlockComment { start, .. }, _) => Scan::BlockComment { start, star: b == b'*' },
+            (state, _) => state,
+        };
+    }
+
+    /// Checks that only whitespace and comments follow the value.
+    fn scan_trailing(&mut self) -> Result<(), ParseError> {
+        while self.scanned < self.buffer.len() {
+            let b = self.buffer[self.scanned];
+            self.scanned += 1;
+            match self.state {
+                Scan::Code if is_ws(b) => {}
+                Scan::Code if b == b'/' && self.options.allow_comments => self.state = Scan::Slash,
+                Scan::Code => return Err(self.trailing_data(self.scanned - 1)),
+                Scan::Slash if matches!(b, b'/' | b'*') => self.state = self.comment_state(b),
+                Scan::Slash => return Err(self.trailing_data(self.scanned - 2)),
+                _ => self.scan_comment(b),
+            }
+        }
+        Ok(())
+    }
+
+    /// Parses the whole buffer as the document.
+    fn parse_buffer(&mut self) -> Result<RdnValue, ParseError> {
+        self.done = true;
+        let text = std::str::from_utf8(&self.buffer).map_err(|e| match e.error_len() {
+            Some(_) => ParseError::new(&self.buffer, e.valid_up_to(), "Invalid UTF-8"),
+            None => ParseError::new(&self.buffer, self.buffer.len(), "Unexpected end of input"),
+        })?;
+        parse_with(text, &self.options)
+    }
+
+    fn trailing_data(&self, offset: usize) -> ParseError {
+        ParseError::new(&self.buffer, offset, "Unexpected data after value")
+    }
+}
+
+fn is_ws(b: u8) -> bool {
+    matches!(b, b' ' | b'\t' | b'\n' | b'\r')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feeds `input` one byte at a time, returning every result.
+    fn feed_bytewise(input: &str) -> Vec<Result<Option<RdnValue>, ParseError>> {
+        let mut parser = IncrementalParser::new();
+        input.as_bytes().chunks(1).map(|chunk| parser.feed(chunk)).collect()
+    }
+
+    #[test]
+    fn value_arrives_with_final_byte() {
+        let input = r#"{"a": [1, "x]}\"y", @2024-01-15, /a\/b]/g], "b": b"AQID"}"#;
+        let results = feed_bytewise(input);
+        let (last, rest) = results.split_last().unwrap();
+        assert!(rest.iter().all(|r| matches!(r, Ok(None))));
+        assert_eq!(last.clone().unwrap().unwrap(), crate::parse(input).unwrap());
+    }
+
+    #[test]
+    fn multibyte_character_split_across_chunks() {
+        let input = "[\"h\u{e9}llo \u{1F600}\"]".as_bytes();
+        for split in 1..input.len() {
+            let mut parser = IncrementalParser::new();
+            assert_eq!(parser.feed(&input[..split]).unwrap(), None);
+            let value = parser.feed(&input[split..]).unwrap().unwrap();
+            assert_eq!(value, RdnValue::Array(vec![RdnValue::String("h\u{e9}llo \u{1F600}".to_string())]));
+        }
+    }
+
+    #[test]
+    fn top_level_scalar_needs_whitespace_or_finish() {
+        let mut parser = IncrementalParser::new();
+        assert_eq!(parser.feed(b"12").unwrap(), None);
+        assert_eq!(parser.feed(b"3").unwrap(), None);
+        assert_eq!(parser.feed(b"\n").unwrap(), Some(RdnValue::Number(123.0)));
+
+        let mut parser = IncrementalParser::new();
+        assert_eq!(parser.feed(b"  tr").unwrap(), None);
+        assert_eq!(parser.feed(b"ue").unwrap(), None);
+        assert_eq!(parser.finish().unwrap(), Some(RdnValue::Bool(true)));
+    }
+
+    #[test]
+    fn finish_after_value_returns_none() {
+        let mut parser = IncrementalParser::new();
+        assert!(parser.feed(b"[] ").unwrap().is_some());
+        assert_eq!(parser.feed(b" \n").unwrap(), None);
+        assert_eq!(parser.finish().unwrap(), None);
+    }
+
+    #[test]
+    fn errors_report_stream_positions() {
+        let mut parser = IncrementalParser::new();
+        assert_eq!(parser.feed(b"[1,").unwrap(), None);
+        assert_eq!(parser.feed(b" 2,]").unwrap_err().to_string(), "Unexpected character ']' at position 6");
+
+        let mut parser = IncrementalParser::new();
+        assert_eq!(parser.feed(b"[1] [2]").unwrap_err().to_string(), "Unexpected data after value at position 4");
+
+        let mut parser = IncrementalParser::new();
+        assert!(parser.feed(b"{}").unwrap().is_some());
+        assert_eq!(parser.feed(b" x").unwrap_err().to_string(), "Unexpected data after value at position 3");
+    }
+
+    #[test]
+    fn finish_with_incomplete_input_is_err() {
+        let mut parser = IncrementalParser::new();
+        assert_eq!(parser.feed(b"[1, 2").unwrap(), None);
+        assert_eq!(parser.finish().unwrap_err().to_string(), "Expected ']' at position 5");
+
+        let mut parser = IncrementalParser::new();
+        assert_eq!(parser.feed(b"\"\xC3").unwrap(), None);
+        assert_eq!(parser.finish().unwrap_err().to_string(), "Unexpected end of input at position 2");
+
+        let mut parser = IncrementalParser::new();
+        assert_eq!(parser.feed(b"[\"\xFF\"]").unwrap_err().to_string(), "Invalid UTF-8 at position 2");
+    }
+
+    #[test]
+    fn options_are_applied() {
+        let options = ParseOptions::default().with_unit_suffixes(&[("ms", 1.0)]);
+        let mut parser = IncrementalParser::with_options(options);
+        assert_eq!(parser.feed(b"[10m").unwrap(), None);
+        assert_eq!(parser.feed(b"s]").unwrap(), Some(RdnValue::Array(vec![RdnValue::Number(10.0)])));
+    }
+
+    #[test]
+    fn single_quoted_strings_hide_brackets() {
+        let options = ParseOptions { allow_single_quotes: true, ..ParseOptions::default() };
+        let mut parser = IncrementalParser::with_options(options);
+        assert_eq!(parser.feed(br#"['a]"\'', "'""#).unwrap(), None);
+        let expected = RdnValue::Array(vec![RdnValue::String("a]\"'".to_string()), RdnValue::String("'".to_string())]);
+        assert_eq!(parser.feed(b"]").unwrap(), Some(expected));
+    }
+
+    #[test]
+    fn regexp_character_class_hides_slash() {
+        for input in ["[/[/]/]", "{\"re\": /a[/\\]]+/g}"] {
+            let results = feed_bytewise(input);
+            let (last, rest) = results.split_last().unwrap();
+            assert!(rest.iter().all(|r| matches!(r, Ok(None))), "{input:?}");
+            assert_eq!(last.clone().unwrap().unwrap(), crate::parse(input).unwrap(), "{input:?}");
+        }
+
+        let mut parser = IncrementalParser::new();
+        assert_eq!(parser.feed(b"/[/]").unwrap(), None);
+        assert_eq!(parser.feed(b"x/").unwrap(), None);
+        assert_eq!(parser.feed(b"i ").unwrap(), Some(crate::parse("/[/]x/i").unwrap()));
+    }
+
+    // --- parse_from_reader tests ---
+
+    /// Yields at most `chunk` bytes per read, then fails if `fail` is set.
+    struct Trickle<'a> {
+        data: &'a [u8],
+        chunk: usize,
+        fail: bool,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.data.is_empty() && self.fail {
+                return Err(std::io::Error::other("connection reset"));
+            }
+            let len = self.chunk.min(buf.len()).min(self.data.len());
+            buf[..len].copy_from_slice(&self.data[..len]);
+            self.data = &self.data[len..];
+            Ok(len)
+        }
+    }
+
+    #[test]
+    fn parse_from_reader_matches_parse() {
+        let input = "  {\"a\": [1, \"\u{e9}\u{1F600}\"], \"b\": Set{@2024-01-15}, \"c\": 42n}\n";
+        let expected = crate::parse(input).unwrap();
+        assert_eq!(parse_from_reader(std::io::Cursor::new(input.as_bytes())).unwrap(), expected);
+        for chunk in [1, 2, 3, 7] {
+            let reader = Trickle { data: input.as_bytes(), chunk, fail: false };
+            assert_eq!(parse_from_reader(reader).unwrap(), expected);
+        }
+        assert_eq!(parse_from_reader(&b"12"[..]).unwrap(), RdnValue::Number(12.0));
+    }
+
+    #[test]
+    fn parse_from_reader_regexp_character_class() {
+        for input in ["/[/]/", "[/[/]/]", "/[/]x/ "] {
+            let expected = crate::parse(input).unwrap();
+            for chunk in [1, 2, 8192] {
+                let reader = Trickle { data: input.as_bytes(), chunk, fail: false };
+                assert_eq!(parse_from_reader(reader).unwrap(), expected, "{input:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn parse_from_reader_errors_match_parse() {
+        for input in ["[1, 2", "{\"a\": }", "[1] 2", "", "   "] {
+            let expected = crate::parse(input).unwrap_err();
+            let reader = Trickle { data: input.as_bytes(), chunk: 2, fail: false };
+            assert_eq!(parse_from_reader(reader).unwrap_err(), expected, "{input:?}");
+        }
+        let err = parse_from_reader(&b"[\"\xC3"[..]).unwrap_err();
+        assert_eq!(err.to_string(), "Unexpected end of input at position 3");
+    }
+
+    #[test]
+    fn parse_from_reader_io_error() {
+        let reader = Trickle { data: b"[1, ", chunk: 3, fail: true };
+        assert_eq!(parse_from_reader(reader).unwrap_err().to_string(), "I/O error: connection reset at position 4");
+    }
+
+    fn comment_parser() -> IncrementalParser {
+        IncrementalParser::with_options(ParseOptions { allow_comments: true, ..ParseOptions::default() })
+    }
+
+    #[test]
+    fn comments_split_across_chunks() {
+        let input = b"// \"[\n[1, /* ] \" */ /a\\/b/, 2] // done\n";
+        for split in 1..input.len() {
+            let mut parser = comment_parser();
+            let first = parser.feed(&input[..split]).unwrap();
+            let second = parser.feed(&input[split..]).unwrap();
+            assert_eq!(first.or(second), crate::parse_with(std::str::from_utf8(input).unwrap(), &parser.options).ok());
+        }
+    }
+
+    #[test]
+    fn comment_ends_top_level_scalar() {
+        let mut parser = comment_parser();
+        assert_eq!(parser.feed(b"42/").unwrap(), None);
+        assert_eq!(parser.feed(b"* x */").unwrap(), Some(RdnValue::Number(42.0)));
+        assert_eq!(parser.finish().unwrap(), None);
+
+        let mut parser = comment_parser();
+        assert!(parser.feed(b"[] /* open").unwrap().is_some());
+        assert_eq!(parser.finish().unwrap_err().to_string(), "Unterminated comment at position 3");
+
+        let mut parser = comment_parser();
+        assert!(parser.feed(b"[] // x\n").unwrap().is_some());
+        assert_eq!(parser.feed(b"/x/").unwrap_err().to_string(), "Unexpected data after value at position 8");
+    }
+}
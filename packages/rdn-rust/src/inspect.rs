@@ -0,0 +1,702 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::mem::size_of;
+
+use crate::query::{children, push_path_step};
+use crate::types::*;
+
+/// Collects the paths of non-finite numbers under `value` into `found`,
+/// returning `true` if the walk should stop early.
+fn collect_non_finite(value: &RdnValue, path: &mut String, fail_fast: bool, found: &mut Vec<String>) -> bool {
+    if let RdnValue::Number(n) = value {
+        if !n.is_finite() {
+            found.push(path.clone());
+            return fail_fast;
+        }
+    }
+    for (i, child) in children(value).into_iter().enumerate() {
+        if let RdnValue::Map(entries) = value {
+            if matches!(entries[i].0, RdnValue::Number(n) if !n.is_finite()) {
+                found.push(format!("{path}[{i}]~"));
+                if fail_fast {
+                    return true;
+                }
+            }
+        }
+        let len = path.len();
+        push_path_step(path, value, i);
+        let stop = collect_non_finite(child, path, fail_fast, found);
+        path.truncate(len);
+        if stop {
+            return true;
+        }
+    }
+    false
+}
+
+/// Compares two `f64`s by JavaScript's SameValueZero: `-0` equals `0`, and
+/// every NaN equals every other.
+fn same_value_zero(x: f64, y: f64) -> bool {
+    x == y || (x.is_nan() && y.is_nan())
+}
+
+/// Feeds an `f64` to `state` so that values comparing equal hash equally:
+/// `-0` hashes as `0`, and every NaN hashes alike.
+fn hash_f64(n: f64, state: &mut impl Hasher) {
+    let canonical = if n.is_nan() { f64::NAN } else if n == 0.0 { 0.0 } else { n };
+    canonical.to_bits().hash(state);
+}
+
+/// Feeds `items` to `state` as a multiset: each item is hashed on its own and
+/// the results are summed, so any reordering gives the same hash.
+fn hash_unordered<T: Hash>(items: &[T], state: &mut impl Hasher) {
+    let sum = items.iter().fold(0u64, |acc, item| {
+        let mut item_state = DefaultHasher::new();
+        item.hash(&mut item_state);
+        acc.wrapping_add(item_state.finish())
+    });
+    items.len().hash(state);
+    sum.hash(state);
+}
+
+/// Compares the variant and contents of two values, recursively and in
+/// order, like a derived `PartialEq` except that Numbers and Date millis
+/// compare by SameValueZero: `-0` equals `0`, and every NaN equals every
+/// other, as in JavaScript's `Set` and `Map`. That keeps `==` reflexive, so
+/// it agrees with [`Hash`] and makes `RdnValue` a lawful [`Eq`].
+impl PartialEq for RdnValue {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (RdnValue::Null, RdnValue::Null) => true,
+            (RdnValue::Bool(x), RdnValue::Bool(y)) => x == y,
+            (RdnValue::Number(x), RdnValue::Number(y)) => same_value_zero(*x, *y),
+            (RdnValue::BigInt(x), RdnValue::BigInt(y)) => x == y,
+            (RdnValue::Decimal(x), RdnValue::Decimal(y)) => x == y,
+            (RdnValue::String(x), RdnValue::String(y)) => x == y,
+            (RdnValue::Array(xs), RdnValue::Array(ys)) | (RdnValue::Set(xs), RdnValue::Set(ys)) => xs == ys,
+            (RdnValue::Object(xs), RdnValue::Object(ys)) => xs == ys,
+            (RdnValue::Date(x), RdnValue::Date(y)) => same_value_zero(x.millis, y.millis),
+            (RdnValue::TimeOnly(x), RdnValue::TimeOnly(y)) => x == y,
+            (RdnValue::Duration(x), RdnValue::Duration(y)) => x == y,
+            (RdnValue::RegExp(x), RdnValue::RegExp(y)) => x == y,
+            (RdnValue::Binary(x), RdnValue::Binary(y)) => x == y,
+            (RdnValue::Map(xs), RdnValue::Map(ys)) => xs == ys,
+            _ => false,
+        }
+    }
+}
+
+/// Hashes the variant and contents of a value, recursively.
+///
+/// Values that compare equal with `==` hash equally, and so do values that
+/// are [equivalent ignoring order](RdnValue::equivalent_ignoring_order):
+/// Array elements are hashed in order, but Set members and Object and Map
+/// entries are not. Numbers and Dates hash by their canonical bit pattern,
+/// with `-0` as `0` and every NaN alike.
+impl Hash for RdnValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.type_name().hash(state);
+        match self {
+            RdnValue::Null => {}
+            RdnValue::Bool(b) => b.hash(state),
+            RdnValue::Number(n) => hash_f64(*n, state),
+            RdnValue::BigInt(bi) => bi.value().hash(state),
+            RdnValue::Decimal(d) => d.value().hash(state),
+            RdnValue::String(s) => s.hash(state),
+            RdnValue::Date(d) => hash_f64(d.millis, state),
+            RdnValue::TimeOnly(t) => (t.hours(), t.minutes(), t.seconds(), t.milliseconds()).hash(state),
+            RdnValue::Duration(d) => d.iso.hash(state),
+            RdnValue::RegExp(re) => (re.source(), re.flags()).hash(state),
+            RdnValue::Binary(bytes) => bytes.hash(state),
+            RdnValue::Array(items) => items.hash(state),
+            RdnValue::Set(items) => hash_unordered(items, state),
+            RdnValue::Object(entries) => hash_unordered(entries, state),
+            RdnValue::Map(entries) => hash_unordered(entries, state),
+        }
+    }
+}
+
+/// `==` is an equivalence relation, NaN included, so any value can be a
+/// `HashSet` member or a `HashMap` key.
+impl Eq for RdnValue {}
+
+/// Returns `true` if `xs` and `ys` hold the same elements under `eq`, as
+/// multisets: order is ignored but the number of repeats must agree.
+fn same_multiset<T>(xs: &[T], ys: &[T], eq: impl Fn(&T, &T) -> bool) -> bool {
+    if xs.len() != ys.len() {
+        return false;
+    }
+    let mut used = vec![false; ys.len()];
+    xs.iter().all(|x| match (0..ys.len()).find(|&j| !used[j] && eq(x, &ys[j])) {
+        Some(j) => {
+            used[j] = true;
+            true
+        }
+        None => false,
+    })
+}
+
+/// Folds `value` and its descendants into `acc` in pre-order.
+fn fold_node<T>(value: &RdnValue, acc: T, f: &mut impl FnMut(T, &RdnValue) -> T) -> T {
+    let mut acc = f(acc, value);
+    match value {
+        RdnValue::Array(items) | RdnValue::Set(items) => {
+            for item in items {
+                acc = fold_node(item, acc, f);
+            }
+        }
+        RdnValue::Object(entries) => {
+            for (_, v) in entries {
+                acc = fold_node(v, acc, f);
+            }
+        }
+        RdnValue::Map(entries) => {
+            for (k, v) in entries {
+                acc = fold_node(k, acc, f);
+                acc = fold_node(v, acc, f);
+            }
+        }
+        _ => {}
+    }
+    acc
+}
+
+/// Returns the distinct top-level keys of an Object in order, or nothing for
+/// any other variant.
+fn object_keys(value: &RdnValue) -> Vec<&str> {
+    let mut keys: Vec<&str> = Vec::new();
+    if let RdnValue::Object(entries) = value {
+        for (k, _) in entries {
+            if !keys.contains(&k.as_str()) {
+                keys.push(k);
+            }
+        }
+    }
+    keys
+}
+
+impl RdnValue {
+    /// Estimates the heap memory owned by this value tree, in bytes.
+    ///
+    /// Sums the allocated capacity of every `String`, `Vec`, and binary buffer
+    /// reachable from `self`, including the inline size of the elements those
+    /// vectors hold. The inline size of `self` is not counted, since it may live
+    /// on the stack or inside a parent container. This is an estimate: allocator
+    /// bookkeeping and alignment padding are ignored, and `BigInt`, `Decimal`,
+    /// and `RegExp` text is counted by length because their buffers are never
+    /// over-allocated.
+    pub fn heap_size(&self) -> usize {
+        match self {
+            RdnValue::Null
+            | RdnValue::Bool(_)
+            | RdnValue::Number(_)
+            | RdnValue::Date(_)
+            | RdnValue::TimeOnly(_) => 0,
+            RdnValue::BigInt(bi) => bi.value().len(),
+            RdnValue::Decimal(d) => d.value().len(),
+            RdnValue::String(s) => s.capacity(),
+            RdnValue::Duration(d) => d.iso.capacity(),
+            RdnValue::RegExp(re) => re.source().len() + re.flags().len(),
+            RdnValue::Binary(bytes) => bytes.capacity(),
+            RdnValue::Array(items) | RdnValue::Set(items) => {
+                items.capacity() * size_of::<RdnValue>() + items.iter().map(RdnValue::heap_size).sum::<usize>()
+            }
+            RdnValue::Object(entries) => {
+                entries.capacity() * size_of::<(String, RdnValue)>()
+                    + entries.iter().map(|(k, v)| k.capacity() + v.heap_size()).sum::<usize>()
+            }
+            RdnValue::Map(entries) => {
+                entries.capacity() * size_of::<(RdnValue, RdnValue)>()
+                    + entries.iter().map(|(k, v)| k.heap_size() + v.heap_size()).sum::<usize>()
+            }
+        }
+    }
+
+    /// Returns a fast, non-cryptographic 64-bit hash of this value tree.
+    ///
+    /// This is the value's [`Hash`] fed to a `DefaultHasher`, so values that
+    /// compare equal with `==` hash equally and the result can key an
+    /// in-memory cache of parsed documents. Array order is significant; Set,
+    /// Object, and Map order is not. The hash is stable within a process run
+    /// only: it is built on the standard library's `DefaultHasher`, whose
+    /// algorithm may change between Rust releases, and the walk may change
+    /// between releases of this crate. Do not persist it or send it to
+    /// another process.
+    pub fn short_hash(&self) -> u64 {
+        let mut state = DefaultHasher::new();
+        self.hash(&mut state);
+        state.finish()
+    }
+
+    /// Returns `true` if `needle` equals this value or any value nested in it.
+    ///
+    /// Uses structural equality (`==`), so `needle` may be a scalar or a whole
+    /// subtree. Object values, Array elements, Set members, and both keys and
+    /// values of Maps are searched; Object keys are not, since they are not
+    /// values. The search stops at the first match.
+    pub fn deep_contains(&self, needle: &RdnValue) -> bool {
+        if self == needle {
+            return true;
+        }
+        match self {
+            RdnValue::Array(items) | RdnValue::Set(items) => items.iter().any(|item| item.deep_contains(needle)),
+            RdnValue::Object(entries) => entries.iter().any(|(_, v)| v.deep_contains(needle)),
+            RdnValue::Map(entries) => entries.iter().any(|(k, v)| k.deep_contains(needle) || v.deep_contains(needle)),
+            _ => false,
+        }
+    }
+
+    /// Compares two value trees, ignoring entry order where it carries no
+    /// meaning.
+    ///
+    /// The rules apply at every depth:
+    ///
+    /// - Arrays are ordered: elements are compared pairwise by position.
+    /// - Objects are unordered: both sides must have the same entries in any
+    ///   order, with the same number of repeats for duplicate keys.
+    /// - Maps are unordered in the same way, with keys compared by this
+    ///   equivalence too.
+    /// - Sets are unordered multisets of members.
+    /// - Everything else uses `==`, so `NaN` is equivalent to itself.
+    pub fn equivalent_ignoring_order(&self, other: &RdnValue) -> bool {
+        match (self, other) {
+            (RdnValue::Array(xs), RdnValue::Array(ys)) => {
+                xs.len() == ys.len() && xs.iter().zip(ys).all(|(x, y)| x.equivalent_ignoring_order(y))
+            }
+            (RdnValue::Set(xs), RdnValue::Set(ys)) => same_multiset(xs, ys, |x, y| x.equivalent_ignoring_order(y)),
+            (RdnValue::Object(xs), RdnValue::Object(ys)) => {
+                same_multiset(xs, ys, |(kx, vx), (ky, vy)| kx == ky && vx.equivalent_ignoring_order(vy))
+            }
+            (RdnValue::Map(xs), RdnValue::Map(ys)) => same_multiset(xs, ys, |(kx, vx), (ky, vy)| {
+                kx.equivalent_ignoring_order(ky) && vx.equivalent_ignoring_order(vy)
+            }),
+            _ => self == other,
+        }
+    }
+
+    /// Returns `true` if this value matches `pattern`, where the string `"*"`
+    /// anywhere in the pattern is a wildcard matching any value, including a
+    /// whole subtree.
+    ///
+    /// Apart from wildcards, matching is structural:
+    ///
+    /// - An Object pattern matches an Object that has every pattern key with
+    ///   a matching value; keys not named in the pattern are ignored. With
+    ///   duplicate keys, the first entry is used.
+    /// - A Map pattern works the same way, with pattern keys compared to the
+    ///   value's keys by `==` (wildcards apply to Map values, not keys).
+    /// - An Array pattern matches an Array of the same length whose elements
+    ///   match position by position.
+    /// - A Set pattern matches a Set in which every pattern member matches
+    ///   some member.
+    /// - Any other pattern must equal the value (`==`).
+    ///
+    /// There is no escape for a literal `"*"`; a wildcard matches it anyway.
+    pub fn matches_pattern(&self, pattern: &RdnValue) -> bool {
+        match (pattern, self) {
+            (RdnValue::String(p), _) if p == "*" => true,
+            (RdnValue::Object(ps), RdnValue::Object(vs)) => ps.iter().all(|(pk, pv)| {
+                vs.iter().find(|(k, _)| k == pk).is_some_and(|(_, v)| v.matches_pattern(pv))
+            }),
+            (RdnValue::Map(ps), RdnValue::Map(vs)) => ps.iter().all(|(pk, pv)| {
+                vs.iter().find(|(k, _)| k == pk).is_some_and(|(_, v)| v.matches_pattern(pv))
+            }),
+            (RdnValue::Array(ps), RdnValue::Array(vs)) => {
+                ps.len() == vs.len() && vs.iter().zip(ps).all(|(v, p)| v.matches_pattern(p))
+            }
+            (RdnValue::Set(ps), RdnValue::Set(vs)) => ps.iter().all(|p| vs.iter().any(|v| v.matches_pattern(p))),
+            _ => self == pattern,
+        }
+    }
+
+    /// Reduces the whole tree to a single result by calling `f` on every
+    /// value, threading the accumulator through.
+    ///
+    /// The traversal is pre-order: a container is visited before its
+    /// children, and children are visited in document order. Map entries
+    /// visit the key, then the value; Object keys are plain strings and are
+    /// not visited.
+    ///
+    /// ```
+    /// use rdn::RdnValue;
+    ///
+    /// let v = rdn::parse(r#"{"a": 1, "b": [2, {"c": 3}]}"#).unwrap();
+    /// let sum = v.fold(0.0, |acc, node| match node {
+    ///     RdnValue::Number(n) => acc + n,
+    ///     _ => acc,
+    /// });
+    /// assert_eq!(sum, 6.0);
+    /// ```
+    pub fn fold<T>(&self, init: T, mut f: impl FnMut(T, &RdnValue) -> T) -> T {
+        fold_node(self, init, &mut f)
+    }
+
+    /// Returns the top-level Object keys present in both `self` and `other`,
+    /// in `self`'s order.
+    ///
+    /// These key-set operations look only at which keys exist, never at their
+    /// values, and only at the top level; use [`RdnValue::get_path`] to
+    /// compare nested Objects. Duplicate keys are reported once, and a
+    /// non-Object on either side has no keys.
+    pub fn key_intersection<'a>(&'a self, other: &RdnValue) -> Vec<&'a str> {
+        let theirs = object_keys(other);
+        object_keys(self).into_iter().filter(|k| theirs.contains(k)).collect()
+    }
+
+    /// Returns the top-level Object keys of `self` that `other` lacks, in
+    /// `self`'s order. See [`RdnValue::key_intersection`].
+    pub fn key_difference<'a>(&'a self, other: &RdnValue) -> Vec<&'a str> {
+        let theirs = object_keys(other);
+        object_keys(self).into_iter().filter(|k| !theirs.contains(k)).collect()
+    }
+
+    /// Returns the top-level Object keys found on exactly one side: those only
+    /// in `self`, in its order, followed by those only in `other`, in its
+    /// order. See [`RdnValue::key_intersection`].
+    pub fn key_symmetric_difference<'a>(&'a self, other: &'a RdnValue) -> Vec<&'a str> {
+        let mut keys = self.key_difference(other);
+        keys.extend(other.key_difference(self));
+        keys
+    }
+
+    /// Checks that every number in the tree is finite.
+    ///
+    /// Returns `Err` with the path of each `NaN`, `Infinity`, or `-Infinity`,
+    /// in document order, using the query syntax of [`RdnValue::select`]
+    /// (`$.readings[3]`). A non-finite Map key is reported as its entry path
+    /// followed by `~` (`$.m[0]~`), since keys have no query syntax of their
+    /// own. With `fail_fast`, the walk stops at the first hit and the error
+    /// holds a single path.
+    pub fn check_finite(&self, fail_fast: bool) -> Result<(), Vec<String>> {
+        let mut found = Vec::new();
+        collect_non_finite(self, &mut String::from("$"), fail_fast, &mut found);
+        if found.is_empty() {
+            Ok(())
+        } else {
+            Err(found)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn heap_size_scalars_are_zero() {
+        assert_eq!(RdnValue::Null.heap_size(), 0);
+        assert_eq!(RdnValue::Number(1.5).heap_size(), 0);
+        assert_eq!(RdnValue::Date(RdnDate { millis: 0.0 }).heap_size(), 0);
+    }
+
+    #[test]
+    fn heap_size_counts_string_capacity() {
+        let mut s = String::with_capacity(64);
+        s.push_str("abc");
+        assert_eq!(RdnValue::String(s).heap_size(), 64);
+    }
+
+    #[test]
+    fn heap_size_counts_binary_capacity() {
+        assert_eq!(RdnValue::Binary(Vec::with_capacity(100)).heap_size(), 100);
+    }
+
+    #[test]
+    fn heap_size_counts_vec_slots_and_children() {
+        let mut items = Vec::with_capacity(4);
+        items.push(RdnValue::String("hello".to_string()));
+        let expected = 4 * size_of::<RdnValue>() + 5;
+        assert_eq!(RdnValue::Array(items).heap_size(), expected);
+    }
+
+    #[test]
+    fn heap_size_counts_object_keys() {
+        let entries = vec![("key".to_string(), RdnValue::Null)];
+        let expected = size_of::<(String, RdnValue)>() + 3;
+        assert_eq!(RdnValue::Object(entries).heap_size(), expected);
+    }
+
+    #[test]
+    fn heap_size_counts_map_keys_and_values() {
+        let entries = vec![(RdnValue::String("k".to_string()), RdnValue::Binary(vec![1, 2]))];
+        let expected = size_of::<(RdnValue, RdnValue)>() + 1 + 2;
+        assert_eq!(RdnValue::Map(entries).heap_size(), expected);
+    }
+
+    // --- short_hash tests ---
+
+    #[test]
+    fn short_hash_equal_values_match() {
+        let a = RdnValue::Object(vec![("k".to_string(), RdnValue::Set(vec![RdnValue::Number(1.0)]))]);
+        assert_eq!(a.short_hash(), a.clone().short_hash());
+        assert_eq!(RdnValue::Number(-0.0).short_hash(), RdnValue::Number(0.0).short_hash());
+    }
+
+    #[test]
+    fn short_hash_distinguishes_types_and_contents() {
+        let s = RdnValue::String("1".to_string());
+        let n = RdnValue::Number(1.0);
+        let arr = RdnValue::Array(vec![n.clone()]);
+        let set = RdnValue::Set(vec![n.clone()]);
+        assert_ne!(s.short_hash(), n.short_hash());
+        assert_ne!(arr.short_hash(), set.short_hash());
+        assert_ne!(n.short_hash(), RdnValue::Number(2.0).short_hash());
+    }
+
+    #[test]
+    fn short_hash_is_order_sensitive() {
+        let ab = RdnValue::Array(vec![RdnValue::Bool(true), RdnValue::Null]);
+        let ba = RdnValue::Array(vec![RdnValue::Null, RdnValue::Bool(true)]);
+        assert_ne!(ab.short_hash(), ba.short_hash());
+    }
+
+    #[test]
+    fn short_hash_ignores_set_object_and_map_order() {
+        let n = |x: f64| RdnValue::Number(x);
+        let set_ab = RdnValue::Set(vec![n(1.0), RdnValue::String("b".to_string())]);
+        let set_ba = RdnValue::Set(vec![RdnValue::String("b".to_string()), n(1.0)]);
+        assert_eq!(set_ab.short_hash(), set_ba.short_hash());
+        assert_eq!(
+            entries(&[("a", 1.0), ("b", 2.0)]).short_hash(),
+            entries(&[("b", 2.0), ("a", 1.0)]).short_hash()
+        );
+        let map_ab = RdnValue::Map(vec![(set_ab.clone(), n(1.0)), (RdnValue::Null, n(2.0))]);
+        let map_ba = RdnValue::Map(vec![(RdnValue::Null, n(2.0)), (set_ba.clone(), n(1.0))]);
+        assert!(map_ab.equivalent_ignoring_order(&map_ba));
+        assert_eq!(map_ab.short_hash(), map_ba.short_hash());
+        // Repeats still count.
+        assert_ne!(RdnValue::Set(vec![n(1.0), n(1.0)]).short_hash(), RdnValue::Set(vec![n(1.0)]).short_hash());
+    }
+
+    #[test]
+    fn values_work_as_hash_set_members() {
+        use std::collections::HashSet;
+
+        let mut seen = HashSet::new();
+        assert!(seen.insert(entries(&[("a", 1.0), ("b", 2.0)])));
+        assert!(!seen.insert(entries(&[("a", 1.0), ("b", 2.0)])));
+        assert!(seen.insert(RdnValue::Number(0.0)));
+        assert!(!seen.insert(RdnValue::Number(-0.0)));
+        assert!(seen.contains(&RdnValue::Number(0.0)));
+        assert!(!seen.contains(&RdnValue::Number(f64::NAN)));
+        assert!(seen.insert(RdnValue::Number(f64::NAN)));
+        assert!(!seen.insert(RdnValue::Number(-f64::NAN)));
+        assert!(seen.contains(&RdnValue::Number(f64::NAN)));
+        assert!(seen.insert(RdnValue::Date(RdnDate { millis: f64::NAN })));
+        assert!(seen.contains(&RdnValue::Date(RdnDate { millis: f64::NAN })));
+        assert_eq!(seen.len(), 4);
+    }
+
+    // --- deep_contains tests ---
+
+    fn nested() -> RdnValue {
+        RdnValue::Object(vec![(
+            "a".to_string(),
+            RdnValue::Array(vec![
+                RdnValue::Set(vec![RdnValue::String("token".to_string())]),
+                RdnValue::Map(vec![(RdnValue::Number(7.0), RdnValue::Array(vec![RdnValue::Bool(true)]))]),
+            ]),
+        )])
+    }
+
+    #[test]
+    fn deep_contains_finds_nested_scalars() {
+        let v = nested();
+        assert!(v.deep_contains(&RdnValue::String("token".to_string())));
+        assert!(v.deep_contains(&RdnValue::Number(7.0)));
+        assert!(v.deep_contains(&RdnValue::Bool(true)));
+        assert!(!v.deep_contains(&RdnValue::Bool(false)));
+    }
+
+    #[test]
+    fn deep_contains_matches_subtrees_and_self() {
+        let v = nested();
+        assert!(v.deep_contains(&RdnValue::Array(vec![RdnValue::Bool(true)])));
+        assert!(v.deep_contains(&v));
+    }
+
+    #[test]
+    fn deep_contains_ignores_object_keys() {
+        assert!(!nested().deep_contains(&RdnValue::String("a".to_string())));
+    }
+
+    // --- equivalent_ignoring_order tests ---
+
+    fn entries(pairs: &[(&str, f64)]) -> RdnValue {
+        RdnValue::Object(pairs.iter().map(|&(k, v)| (k.to_string(), RdnValue::Number(v))).collect())
+    }
+
+    #[test]
+    fn equivalent_ignores_object_and_map_order() {
+        assert!(entries(&[("a", 1.0), ("b", 2.0)]).equivalent_ignoring_order(&entries(&[("b", 2.0), ("a", 1.0)])));
+        let m1 = RdnValue::Map(vec![(RdnValue::Number(1.0), RdnValue::Null), (RdnValue::Bool(true), RdnValue::Null)]);
+        let m2 = RdnValue::Map(vec![(RdnValue::Bool(true), RdnValue::Null), (RdnValue::Number(1.0), RdnValue::Null)]);
+        assert!(m1.equivalent_ignoring_order(&m2));
+    }
+
+    #[test]
+    fn equivalent_ignores_set_order_recursively() {
+        let a = RdnValue::Set(vec![entries(&[("x", 1.0), ("y", 2.0)]), RdnValue::Null]);
+        let b = RdnValue::Set(vec![RdnValue::Null, entries(&[("y", 2.0), ("x", 1.0)])]);
+        assert!(a.equivalent_ignoring_order(&b));
+    }
+
+    #[test]
+    fn equivalent_compares_map_keys_by_equivalence() {
+        let set = |xs: &[f64]| RdnValue::Set(xs.iter().map(|&x| RdnValue::Number(x)).collect());
+        let a = RdnValue::Map(vec![(set(&[1.0, 2.0]), set(&[3.0, 4.0])), (RdnValue::Null, RdnValue::Null)]);
+        let b = RdnValue::Map(vec![(RdnValue::Null, RdnValue::Null), (set(&[2.0, 1.0]), set(&[4.0, 3.0]))]);
+        assert!(a.equivalent_ignoring_order(&b));
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn equivalent_keeps_array_order() {
+        let a = RdnValue::Array(vec![RdnValue::Number(1.0), RdnValue::Number(2.0)]);
+        let b = RdnValue::Array(vec![RdnValue::Number(2.0), RdnValue::Number(1.0)]);
+        assert!(!a.equivalent_ignoring_order(&b));
+    }
+
+    #[test]
+    fn equivalent_counts_repeats() {
+        let a = entries(&[("a", 1.0), ("a", 1.0), ("b", 2.0)]);
+        let b = entries(&[("a", 1.0), ("b", 2.0), ("b", 2.0)]);
+        assert!(!a.equivalent_ignoring_order(&b));
+        assert!(!entries(&[("a", 1.0)]).equivalent_ignoring_order(&entries(&[("a", 2.0)])));
+    }
+
+    // --- matches_pattern tests ---
+
+    fn rdn(input: &str) -> RdnValue {
+        crate::parse(input).unwrap()
+    }
+
+    #[test]
+    fn pattern_wildcard_field() {
+        let pattern = rdn(r#"{"type": "user", "id": "*"}"#);
+        assert!(rdn(r#"{"type": "user", "id": 7, "name": "ada"}"#).matches_pattern(&pattern));
+        assert!(rdn(r#"{"id": {"nested": [1]}, "type": "user"}"#).matches_pattern(&pattern));
+        assert!(!rdn(r#"{"type": "admin", "id": 7}"#).matches_pattern(&pattern));
+        assert!(!rdn(r#"{"type": "user"}"#).matches_pattern(&pattern));
+    }
+
+    #[test]
+    fn pattern_wildcard_at_depth() {
+        let pattern = rdn(r#"{"rule": {"match": ["GET", "*"]}}"#);
+        assert!(rdn(r#"{"rule": {"match": ["GET", "/a"]}}"#).matches_pattern(&pattern));
+        assert!(!rdn(r#"{"rule": {"match": ["GET", "/a", "x"]}}"#).matches_pattern(&pattern));
+        assert!(rdn("[1, 2]").matches_pattern(&rdn(r#""*""#)));
+    }
+
+    #[test]
+    fn pattern_maps_and_sets() {
+        let map = RdnValue::Map(vec![(RdnValue::Number(1.0), RdnValue::Bool(true)), (RdnValue::Null, RdnValue::Null)]);
+        let pattern = RdnValue::Map(vec![(RdnValue::Number(1.0), RdnValue::String("*".to_string()))]);
+        assert!(map.matches_pattern(&pattern));
+        let set = RdnValue::Set(vec![RdnValue::Number(1.0), RdnValue::Number(2.0)]);
+        assert!(set.matches_pattern(&RdnValue::Set(vec![RdnValue::Number(2.0)])));
+        assert!(!set.matches_pattern(&RdnValue::Set(vec![RdnValue::Number(3.0)])));
+    }
+
+    #[test]
+    fn pattern_scalars_need_equality() {
+        assert!(rdn("1").matches_pattern(&rdn("1")));
+        assert!(!rdn("1").matches_pattern(&rdn(r#""1""#)));
+        assert!(!rdn("[]").matches_pattern(&rdn("{}")));
+    }
+
+    // --- fold tests ---
+
+    #[test]
+    fn fold_visits_pre_order() {
+        let map = RdnValue::Map(vec![(
+            RdnValue::String("k".to_string()),
+            RdnValue::Set(vec![RdnValue::Number(3.0)]),
+        )]);
+        let v = RdnValue::Object(vec![("a".to_string(), rdn("[1, 2]")), ("b".to_string(), map)]);
+        let order = v.fold(Vec::new(), |mut acc, node| {
+            acc.push(match node {
+                RdnValue::Number(n) => n.to_string(),
+                RdnValue::String(s) => s.clone(),
+                other => other.type_name().to_string(),
+            });
+            acc
+        });
+        assert_eq!(order, ["Object", "Array", "1", "2", "Map", "k", "Set", "3"]);
+    }
+
+    #[test]
+    fn fold_collects_strings() {
+        let v = rdn(r#"["x", {"y": "z"}, 1]"#);
+        let strings = v.fold(Vec::new(), |mut acc, node| {
+            if let RdnValue::String(s) = node {
+                acc.push(s.as_str().to_owned());
+            }
+            acc
+        });
+        assert_eq!(strings, ["x", "z"]);
+        assert_eq!(RdnValue::Null.fold(0, |acc, _| acc + 1), 1);
+    }
+
+    // --- key set tests ---
+
+    #[test]
+    fn key_set_operations() {
+        let a = rdn(r#"{"host": 1, "port": 2, "debug": true, "host": 3}"#);
+        let b = rdn(r#"{"timeout": 5, "port": 80, "host": "x"}"#);
+        assert_eq!(a.key_intersection(&b), ["host", "port"]);
+        assert_eq!(a.key_difference(&b), ["debug"]);
+        assert_eq!(b.key_difference(&a), ["timeout"]);
+        assert_eq!(a.key_symmetric_difference(&b), ["debug", "timeout"]);
+    }
+
+    #[test]
+    fn key_set_operations_on_non_objects() {
+        let a = rdn(r#"{"a": 1}"#);
+        let list = rdn(r#"["a"]"#);
+        assert!(a.key_intersection(&list).is_empty());
+        assert_eq!(a.key_difference(&list), ["a"]);
+        assert!(list.key_difference(&a).is_empty());
+        assert_eq!(list.key_symmetric_difference(&a), ["a"]);
+    }
+
+    // --- check_finite tests ---
+
+    fn readings() -> RdnValue {
+        RdnValue::Object(vec![
+            ("ok".to_string(), RdnValue::Number(1.0)),
+            (
+                "readings".to_string(),
+                RdnValue::Array(vec![RdnValue::Number(f64::NAN), RdnValue::Number(2.0), RdnValue::Number(f64::INFINITY)]),
+            ),
+            (
+                "m".to_string(),
+                RdnValue::Map(vec![(RdnValue::Number(f64::NEG_INFINITY), RdnValue::Null)]),
+            ),
+        ])
+    }
+
+    #[test]
+    fn check_finite_passes_finite_tree() {
+        let v = RdnValue::Array(vec![RdnValue::Number(0.0), RdnValue::Number(-1e300)]);
+        assert_eq!(v.check_finite(false), Ok(()));
+        assert_eq!(v.check_finite(true), Ok(()));
+    }
+
+    #[test]
+    fn check_finite_reports_all_paths() {
+        let err = readings().check_finite(false).unwrap_err();
+        assert_eq!(err, vec!["$.readings[0]", "$.readings[2]", "$.m[0]~"]);
+    }
+
+    #[test]
+    fn check_finite_fail_fast_stops_at_first() {
+        let err = readings().check_finite(true).unwrap_err();
+        assert_eq!(err, vec!["$.readings[0]"]);
+    }
+
+    #[test]
+    fn check_finite_root_number() {
+        assert_eq!(RdnValue::Number(f64::NAN).check_finite(false), Err(vec!["$".to_string()]));
+    }
+}
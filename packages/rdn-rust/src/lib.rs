@@ -3,6 +3,7 @@
 //! Supported types beyond JSON:
 //! - `Date`: `@2024-01-15T10:30:00.000Z`
 //! - `BigInt`: `42n`
+//! - `Decimal`: `3.14m` (with `ParseOptions::allow_decimal_literals`)
 //! - `RegExp`: `/pattern/flags`
 //! - `Binary`: `b"base64..."` or `x"hex..."`
 //! - `Map`: `Map{"a" => 1}` or `{"a" => 1}`
@@ -15,7 +16,27 @@
 mod types;
 mod parser;
 mod serializer;
+mod query;
+mod date;
+mod float;
+mod encoding;
+mod coerce;
+mod inspect;
+mod transform;
+mod convert;
+mod access;
+mod migrate;
+mod incremental;
+#[cfg(test)]
+pub(crate) mod test_util;
 
 pub use types::*;
-pub use parser::parse;
-pub use serializer::stringify;
+pub use parser::{parse, parse_partial, parse_spanned, parse_with, LoneSurrogates, ParseError, ParseOptions, SpanTree, TruncationInfo};
+pub use serializer::{stringify, stringify_pretty, stringify_to_writer, stringify_with, BinaryEncoding, Indent, Newline, QuoteKeys, StringifyOptions};
+pub use coerce::{parse_json_with_coercions, CoerceError, Coercion};
+pub use access::RdnAccessor;
+pub use migrate::Migrations;
+pub use incremental::{parse_from_reader, IncrementalParser};
+pub use transform::PreviewOptions;
+#[cfg(feature = "unicode-normalization")]
+pub use transform::NormalizationForm;
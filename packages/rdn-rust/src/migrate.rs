@@ -0,0 +1,190 @@
+use std::collections::BTreeMap;
+
+use crate::types::*;
+
+/// The conventional top-level field holding a document's schema version.
+const VERSION_KEY: &str = "$version";
+
+type Migration = Box<dyn Fn(RdnValue) -> RdnValue>;
+
+/// A registry of schema migrations, each upgrading a document from version
+/// `N` to `N + 1`.
+///
+/// ```
+/// use rdn::{Migrations, RdnValue};
+///
+/// let mut migrations = Migrations::new();
+/// migrations.register(1, |mut doc| {
+///     if let RdnValue::Object(entries) = &mut doc {
+///         entries.push(("port".to_string(), RdnValue::Number(8080.0)));
+///     }
+///     doc
+/// });
+///
+/// let old = RdnValue::Object(vec![("$version".to_string(), RdnValue::Number(1.0))]);
+/// let new = migrations.migrate(old, 2).unwrap();
+/// assert_eq!(new.schema_version(), Some(2));
+/// ```
+#[derive(Default)]
+pub struct Migrations {
+    steps: BTreeMap<u64, Migration>,
+}
+
+impl Migrations {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the migration from version `from` to `from + 1`, replacing
+    /// any migration already registered for `from`.
+    ///
+    /// The function does not need to update `$version`; [`Migrations::migrate`]
+    /// does that after each step.
+    pub fn register(&mut self, from: u64, migration: impl Fn(RdnValue) -> RdnValue + 'static) -> &mut Self {
+        self.steps.insert(from, Box::new(migration));
+        self
+    }
+
+    /// Runs the registered migrations in sequence until `value` reaches
+    /// version `target`.
+    ///
+    /// After each step, the top-level `$version` field is set to the new
+    /// version. A document already at `target` is returned unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the document has no readable version, is newer than
+    /// `target`, a step in the chain is not registered, or a migration
+    /// returns something other than an Object.
+    pub fn migrate(&self, mut value: RdnValue, target: u64) -> Result<RdnValue, String> {
+        let mut version = value
+            .schema_version()
+            .ok_or_else(|| format!("document has no valid {VERSION_KEY} field"))?;
+        if version > target {
+            return Err(format!("document version {version} is newer than target version {target}"));
+        }
+        while version < target {
+            let migration = self
+                .steps
+                .get(&version)
+                .ok_or_else(|| format!("no migration registered from version {version}"))?;
+            value = migration(value);
+            let RdnValue::Object(entries) = &mut value else {
+                return Err(format!(
+                    "migration from version {version} returned {}, expected Object",
+                    value.type_name()
+                ));
+            };
+            version += 1;
+            let stamp = RdnValue::Number(version as f64);
+            match entries.iter_mut().find(|(k, _)| k == VERSION_KEY) {
+                Some((_, v)) => *v = stamp,
+                None => entries.insert(0, (VERSION_KEY.to_string(), stamp)),
+            }
+        }
+        Ok(value)
+    }
+}
+
+impl RdnValue {
+    /// Returns the schema version stored in the top-level `$version` field.
+    ///
+    /// The field must be a non-negative integer `Number` or a `BigInt` that
+    /// fits in `u64`. Returns `None` if this is not an Object, the field is
+    /// missing, or it holds anything else.
+    pub fn schema_version(&self) -> Option<u64> {
+        let RdnValue::Object(entries) = self else {
+            return None;
+        };
+        match entries.iter().find(|(k, _)| k == VERSION_KEY).map(|(_, v)| v)? {
+            // Every integer up to 2^53 is exactly representable as an f64.
+            RdnValue::Number(n) if n.fract() == 0.0 && (0.0..=9_007_199_254_740_992.0).contains(n) => Some(*n as u64),
+            RdnValue::BigInt(bi) => bi.value().parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc(version: RdnValue, fields: Vec<(&str, RdnValue)>) -> RdnValue {
+        let mut entries = vec![(VERSION_KEY.to_string(), version)];
+        entries.extend(fields.into_iter().map(|(k, v)| (k.to_string(), v)));
+        RdnValue::Object(entries)
+    }
+
+    fn rename(from: &'static str, to: &'static str) -> impl Fn(RdnValue) -> RdnValue {
+        move |mut value| {
+            if let RdnValue::Object(entries) = &mut value {
+                for (k, _) in entries.iter_mut().filter(|(k, _)| k == from) {
+                    *k = to.to_string();
+                }
+            }
+            value
+        }
+    }
+
+    #[test]
+    fn schema_version_reads_integer_field() {
+        assert_eq!(doc(RdnValue::Number(3.0), vec![]).schema_version(), Some(3));
+        let big = RdnValue::BigInt(BigInt::new("12").unwrap());
+        assert_eq!(doc(big, vec![]).schema_version(), Some(12));
+    }
+
+    #[test]
+    fn schema_version_rejects_other_values() {
+        assert_eq!(doc(RdnValue::Number(1.5), vec![]).schema_version(), None);
+        assert_eq!(doc(RdnValue::Number(-1.0), vec![]).schema_version(), None);
+        assert_eq!(doc(RdnValue::String("1".to_string()), vec![]).schema_version(), None);
+        assert_eq!(RdnValue::Object(vec![]).schema_version(), None);
+        assert_eq!(RdnValue::Array(vec![]).schema_version(), None);
+    }
+
+    #[test]
+    fn migrate_runs_steps_in_order() {
+        let mut migrations = Migrations::new();
+        migrations.register(1, rename("host", "hostname")).register(2, rename("hostname", "server"));
+        let old = doc(RdnValue::Number(1.0), vec![("host", RdnValue::Null)]);
+        let new = migrations.migrate(old, 3).unwrap();
+        assert_eq!(new, doc(RdnValue::Number(3.0), vec![("server", RdnValue::Null)]));
+    }
+
+    #[test]
+    fn migrate_at_target_is_unchanged() {
+        let current = doc(RdnValue::Number(2.0), vec![]);
+        assert_eq!(Migrations::new().migrate(current.clone(), 2).unwrap(), current);
+    }
+
+    #[test]
+    fn migrate_restores_dropped_version_field() {
+        let mut migrations = Migrations::new();
+        migrations.register(1, |_| RdnValue::Object(vec![]));
+        let new = migrations.migrate(doc(RdnValue::Number(1.0), vec![]), 2).unwrap();
+        assert_eq!(new, doc(RdnValue::Number(2.0), vec![]));
+    }
+
+    #[test]
+    fn migrate_errors() {
+        let mut migrations = Migrations::new();
+        migrations.register(1, |_| RdnValue::Null);
+        assert_eq!(
+            migrations.migrate(RdnValue::Object(vec![]), 2).unwrap_err(),
+            "document has no valid $version field"
+        );
+        assert_eq!(
+            migrations.migrate(doc(RdnValue::Number(3.0), vec![]), 2).unwrap_err(),
+            "document version 3 is newer than target version 2"
+        );
+        assert_eq!(
+            migrations.migrate(doc(RdnValue::Number(0.0), vec![]), 2).unwrap_err(),
+            "no migration registered from version 0"
+        );
+        assert_eq!(
+            migrations.migrate(doc(RdnValue::Number(1.0), vec![]), 2).unwrap_err(),
+            "migration from version 1 returned Null, expected Object"
+        );
+    }
+}
@@ -1,10 +1,72 @@
+use crate::date::{parse_iso_datetime, MAX_DATE_MILLIS};
+use crate::encoding::{decode_base32, decode_base64, decode_hex};
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+
 use crate::types::*;
 
+/// An error from parsing RDN text, with the location of the first offending
+/// byte.
+///
+/// It displays as the message followed by the byte offset, e.g.
+/// `Expected ':' at position 5`; [`ParseError::line`] and
+/// [`ParseError::column`] locate the same byte for human readers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    message: String,
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+impl ParseError {
+    /// Creates an error at byte `offset` of `input`, working out its line and
+    /// column.
+    pub(crate) fn new(input: &[u8], offset: usize, message: &str) -> Self {
+        let before = &input[..offset.min(input.len())];
+        let line_start = before.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+        // Count characters, not bytes: skip UTF-8 continuation bytes.
+        let column = before[line_start..].iter().filter(|&&b| b & 0xC0 != 0x80).count() + 1;
+        let line = before.iter().filter(|&&b| b == b'\n').count() + 1;
+        ParseError { message: message.to_string(), offset, line, column }
+    }
+
+    /// The description of what went wrong, without the location.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The byte offset of the first offending byte.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The 1-based line of the offending byte. Lines end at `\n`, so a
+    /// `\r\n` ending counts once.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// The 1-based column of the offending byte, counted in characters.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.offset)
+    }
+}
+
+impl Error for ParseError {}
+
 /// Parse an RDN string into an `RdnValue`.
 ///
 /// # Errors
 ///
-/// Returns an error string if the input is malformed.
+/// Returns a [`ParseError`] if the input is malformed.
 ///
 /// # Examples
 ///
@@ -13,20 +75,2570 @@ use crate::types::*;
 ///
 /// let value = parse(r#"{"name": "RDN", "version": 42n}"#).unwrap();
 /// ```
-pub fn parse(input: &str) -> Result<RdnValue, String> {
-    // TODO: Implement recursive-descent parser
-    // The parser should handle:
-    // 1. All JSON types (null, boolean, number, string, array, object)
-    // 2. Special numbers: NaN, Infinity, -Infinity
-    // 3. BigInt: 42n, -123n
-    // 4. DateTime: @2024-01-15T10:30:00.000Z, @2024-01-15, @1705312200
-    // 5. TimeOnly: @14:30:00, @23:59:59.999
-    // 6. Duration: @P1Y2M3DT4H5M6S
-    // 7. RegExp: /pattern/flags
-    // 8. Binary: b"base64...", x"hex..."
-    // 9. Map: Map{k => v}, {k => v}
-    // 10. Set: Set{1, 2}, {"a", "b"}
-    // 11. Tuple: (1, 2, 3)
-    // 12. Brace disambiguation: { → Object vs Map vs Set
-    Err("Not implemented".to_string())
+pub fn parse(input: &str) -> Result<RdnValue, ParseError> {
+    parse_with(input, &ParseOptions::default())
+}
+
+/// Options for [`parse_with`]. The default accepts exactly the syntax that
+/// [`parse`] does.
+#[derive(Debug, Clone)]
+pub struct ParseOptions {
+    /// Unit suffixes allowed directly after a number, each with the factor
+    /// the number is multiplied by. `("ms", 1.0)` makes `10ms` parse as the
+    /// Number `10`; `("%", 0.01)` makes `50%` parse as `0.5`.
+    ///
+    /// The result is a plain `Number`, so the suffix is lost: serializing
+    /// writes the base number back out, not the original text. While the list
+    /// is non-empty, any other alphabetic or `%` suffix is an error, except
+    /// the `n` of a BigInt literal, and the `m` of a Decimal literal while
+    /// [`ParseOptions::allow_decimal_literals`] is on. A registered `n` or `m`
+    /// takes precedence over those literals. Suffixes starting with `e` or
+    /// `E` never match, since they read as an exponent. Empty by default.
+    pub unit_suffixes: Vec<(String, f64)>,
+    /// Whether `// line` and `/* block */` comments are allowed wherever
+    /// whitespace is. Neither can be mistaken for a RegExp, since a pattern
+    /// can be neither empty nor start with `*`. Off by default.
+    pub allow_comments: bool,
+    /// Whether strings may be written in single quotes, `'like this'`, with
+    /// the same escapes as double-quoted ones plus `\'`. The quote style is
+    /// not recorded: the result is the same `String`, and serializing always
+    /// writes double quotes. Off by default.
+    pub allow_single_quotes: bool,
+    /// Whether string escapes are limited to JSON's. By default `\0`, not
+    /// followed by a digit, is accepted as U+0000, as in JavaScript; strict
+    /// mode rejects it. Off by default.
+    pub strict_json_escapes: bool,
+    /// What to do with a `\uXXXX` escape for a UTF-16 surrogate that is not
+    /// half of a pair, which a Rust string cannot hold. An error by default.
+    pub lone_surrogates: LoneSurrogates,
+    /// Whether Object keys may be bare identifiers, as in `{name: "RDN"}`.
+    /// Like a JavaScript identifier, one starts with an ASCII letter, `_` or
+    /// `$`, and continues with those or digits. A bare word followed by `:`
+    /// is a key even if it is also a literal, so `{true: 1}` has the key
+    /// `"true"`. Off by default.
+    pub allow_unquoted_keys: bool,
+    /// Whether integers may be written in hexadecimal `0xFF`, octal `0o17`,
+    /// or binary `0b1010`, with an optional `-` and an optional `n` suffix
+    /// for a BigInt. Either way the value is converted to decimal, so the
+    /// radix is not kept. A BigInt may have at most
+    /// [`ParseOptions::MAX_RADIX_BIGINT_DIGITS`] digits, since converting it
+    /// takes time quadratic in its length; a Number may have any number, and
+    /// rounds to the nearest `f64`. Off by default.
+    pub allow_radix_literals: bool,
+    /// Whether `_` may separate digits in numbers, as in `1_000_000`,
+    /// `42_000n` or `0xFF_FF`. Each `_` must sit between two digits; the
+    /// separators are dropped before conversion. Off by default.
+    pub allow_numeric_separators: bool,
+    /// Whether infinity may also be spelled as other extended-JSON producers
+    /// write it. The full set accepted is `inf` and `Inf`, each optionally
+    /// signed with `+` or `-`, and `+Infinity`. The result is the same
+    /// `Number` as `Infinity` or `-Infinity`, which is what serializing
+    /// writes. Off by default.
+    pub infinity_aliases: bool,
+    /// Whether `z"..."` is accepted as a Binary literal in standard padded
+    /// base32 (RFC 4648 §6, alphabet `A-Z2-7`, not the extended hex form),
+    /// uppercase only. Off by default.
+    pub allow_base32_literals: bool,
+    /// Whether a number may end in `m` to make a Decimal, as in `3.14m` or
+    /// `-0.50m`. The digits are kept exactly as written, so trailing zeros
+    /// survive; an exponent is not allowed. Decimal literals are an extension
+    /// outside the RDN spec, so the default [`parse`] rejects them. A
+    /// `Decimal` value is still serialized with its `m`, and only reparses
+    /// with this on. Off by default.
+    pub allow_decimal_literals: bool,
+    /// How deeply Arrays, Objects, Maps, Sets and tuples may nest, counting
+    /// the outermost as 1. Deeper input is an error rather than a stack
+    /// overflow. Defaults to [`ParseOptions::DEFAULT_MAX_DEPTH`].
+    pub max_depth: usize,
+    /// The longest string value or Object key allowed, in bytes of UTF-8
+    /// after escapes are decoded. A longer one is an error at its opening
+    /// quote, raised as soon as the limit is passed rather than after the
+    /// whole string is read. Unquoted keys count too. Unlimited
+    /// (`usize::MAX`) by default.
+    pub max_string_len: usize,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            unit_suffixes: Vec::new(),
+            allow_comments: false,
+            allow_single_quotes: false,
+            strict_json_escapes: false,
+            lone_surrogates: LoneSurrogates::Error,
+            allow_unquoted_keys: false,
+            allow_radix_literals: false,
+            allow_numeric_separators: false,
+            infinity_aliases: false,
+            allow_base32_literals: false,
+            allow_decimal_literals: false,
+            max_depth: Self::DEFAULT_MAX_DEPTH,
+            max_string_len: usize::MAX,
+        }
+    }
+}
+
+impl ParseOptions {
+    /// The default [`ParseOptions::max_depth`].
+    pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+    /// The most digits, not counting `_` separators, a `0x`, `0o` or `0b`
+    /// BigInt literal may have.
+    pub const MAX_RADIX_BIGINT_DIGITS: usize = 10_000;
+
+    /// Percentages as fractions, times in milliseconds, and sizes in bytes
+    /// with SI (power of 1000) multiples.
+    pub const COMMON_UNIT_SUFFIXES: &'static [(&'static str, f64)] = &[
+        ("%", 0.01),
+        ("ms", 1.0),
+        ("s", 1000.0),
+        ("KB", 1e3),
+        ("MB", 1e6),
+        ("GB", 1e9),
+    ];
+
+    /// Adds `suffixes` to [`ParseOptions::unit_suffixes`].
+    pub fn with_unit_suffixes(mut self, suffixes: &[(&str, f64)]) -> Self {
+        self.unit_suffixes.extend(suffixes.iter().map(|&(suffix, factor)| (suffix.to_string(), factor)));
+        self
+    }
+}
+
+/// Whether `b` can start an unquoted key; see
+/// [`ParseOptions::allow_unquoted_keys`].
+fn is_identifier_start(b: u8) -> bool {
+    b.is_ascii_alphabetic() || b == b'_' || b == b'$'
+}
+
+/// Whether `b` can follow the first byte of an unquoted key.
+fn is_identifier_continue(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_' || b == b'$'
+}
+
+/// Whether `key` can be written unquoted, for a parser with
+/// [`ParseOptions::allow_unquoted_keys`] to read back exactly.
+pub(crate) fn is_identifier(key: &str) -> bool {
+    let mut bytes = key.bytes();
+    bytes.next().is_some_and(is_identifier_start) && bytes.all(is_identifier_continue)
+}
+
+/// How to parse a lone surrogate escape; see
+/// [`ParseOptions::lone_surrogates`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LoneSurrogates {
+    /// Fail with "Unpaired surrogate in unicode escape".
+    #[default]
+    Error,
+    /// Decode it as U+FFFD REPLACEMENT CHARACTER, as a lossy UTF-16
+    /// decoder would.
+    ReplacementChar,
+}
+
+/// Parse an RDN string into an `RdnValue`, with the extensions enabled in
+/// `options`.
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] if the input is malformed.
+///
+/// # Examples
+///
+/// ```
+/// use rdn::{parse_with, ParseOptions, RdnValue};
+///
+/// let options = ParseOptions::default().with_unit_suffixes(ParseOptions::COMMON_UNIT_SUFFIXES);
+/// let value = parse_with("[50%, 2s, 3KB]", &options).unwrap();
+/// let expected = [0.5, 2000.0, 3000.0].map(RdnValue::Number);
+/// assert_eq!(value, RdnValue::Array(expected.to_vec()));
+/// ```
+pub fn parse_with(input: &str, options: &ParseOptions) -> Result<RdnValue, ParseError> {
+    let mut parser = Parser { src: input, pos: 0, depth: 0, spans: None, options, partial: false, cut: None };
+    parser.parse_document()
+}
+
+/// Parse an RDN string into an `RdnValue`, along with where in `input` each
+/// part of it came from.
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] if the input is malformed.
+///
+/// # Examples
+///
+/// ```
+/// use rdn::parse_spanned;
+///
+/// let input = r#"{"id": [1, 42n]}"#;
+/// let (_, spans) = parse_spanned(input).unwrap();
+/// let (key, value) = spans.entry(0).unwrap();
+/// assert_eq!(&input[key.span()], r#""id""#);
+/// assert_eq!(&input[value.children()[1].span()], "42n");
+/// ```
+pub fn parse_spanned(input: &str) -> Result<(RdnValue, SpanTree), ParseError> {
+    let options = ParseOptions::default();
+    let mut parser =
+        Parser { src: input, pos: 0, depth: 0, spans: Some(Vec::new()), options: &options, partial: false, cut: None };
+    let value = parser.parse_document()?;
+    let tree = parser.spans.and_then(|mut spans| spans.pop()).unwrap_or_default();
+    Ok((value, tree))
+}
+
+/// Parse RDN text that may have been cut short, such as a document whose
+/// download failed part way, keeping as much of it as can be recovered.
+///
+/// Complete input parses just as [`parse`] parses it, with no
+/// [`TruncationInfo`]. When the input instead ends inside a value, every
+/// open Array, tuple, Object, Map and Set is closed where the input ends,
+/// keeping the elements and entries that were complete, and the
+/// `TruncationInfo` gives the offset of the first byte left out. What is
+/// left out is:
+///
+/// - a string, Binary literal or RegExp missing its closing quote or `/`;
+/// - a number, BigInt, `@` literal, `true`, `false`, `null`, `NaN`,
+///   `Infinity` or RegExp that the input ends right after, since more input
+///   could have changed it: `12` may have been `123`, and `/a/` may have
+///   been `/a/g`;
+/// - an Object or Map entry without its value, and the key with it;
+/// - the first value in a bare `{...}` when the input ends before the
+///   separator that says whether it is an Object, Map or Set, leaving an
+///   empty Object.
+///
+/// A nested container cut off inside is kept, recovered the same way. If
+/// nothing at all can be recovered, such as from empty input or a top-level
+/// string without its closing quote, the value is `Null`. The default
+/// [`ParseOptions`] apply.
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] if the input is malformed before it ends. Input
+/// that is only cut short is never an error.
+///
+/// # Examples
+///
+/// ```
+/// use rdn::{parse_partial, RdnValue};
+///
+/// let (value, truncation) = parse_partial(r#"{"a": [1, 2], "b": [3, 4"#).unwrap();
+/// assert_eq!(value, rdn::parse(r#"{"a": [1, 2], "b": [3]}"#).unwrap());
+/// assert_eq!(truncation.unwrap().offset(), 23);
+/// ```
+pub fn parse_partial(input: &str) -> Result<(RdnValue, Option<TruncationInfo>), ParseError> {
+    let options = ParseOptions::default();
+    let mut parser = Parser { src: input, pos: 0, depth: 0, spans: None, options: &options, partial: true, cut: None };
+    parser.parse_partial_document()
+}
+
+/// Where [`parse_partial`] found its input cut short.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TruncationInfo {
+    offset: usize,
+}
+
+impl TruncationInfo {
+    /// The byte offset of the first part of the input left out of the value:
+    /// the start of the value or entry that was dropped, or the end of the
+    /// input if only closing brackets were missing.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+/// The byte ranges of a parsed value and everything in it, laid out like the
+/// value itself. Returned by [`parse_spanned`].
+///
+/// A container's children are its parts in source order: one per element of
+/// an Array, tuple or Set, and two per entry of an Object or Map, the key
+/// then the value. Scalars have none. A span covers the value's own text,
+/// without surrounding whitespace or comments.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpanTree {
+    span: Range<usize>,
+    children: Vec<SpanTree>,
+}
+
+impl SpanTree {
+    /// The byte range of the value in the input.
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+
+    /// The spans of the value's parts; see [`SpanTree`].
+    pub fn children(&self) -> &[SpanTree] {
+        &self.children
+    }
+
+    /// The key and value spans of the `index`th entry of an Object or Map.
+    pub fn entry(&self, index: usize) -> Option<(&SpanTree, &SpanTree)> {
+        match self.children.get(2 * index..2 * index + 2) {
+            Some([key, value]) => Some((key, value)),
+            _ => None,
+        }
+    }
+}
+
+/// Recursive-descent parser state: the input, a byte cursor into it, how many
+/// containers enclose the cursor, and the options in effect.
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+    depth: usize,
+    /// Spans of the values parsed so far whose parent is still being parsed,
+    /// when they are being recorded.
+    spans: Option<Vec<SpanTree>>,
+    options: &'a ParseOptions,
+    /// Whether the input may be cut short; see [`parse_partial`].
+    partial: bool,
+    /// Where the input was found to be cut short, once it has been.
+    cut: Option<usize>,
+}
+
+impl Parser<'_> {
+    fn error<T>(&self, msg: &str) -> Result<T, ParseError> {
+        Err(ParseError::new(self.src.as_bytes(), self.pos, msg))
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.src.as_bytes().get(self.pos).copied()
+    }
+
+    /// Skips whitespace, and comments if [`ParseOptions::allow_comments`] is
+    /// set.
+    fn skip_ws(&mut self) -> Result<(), ParseError> {
+        loop {
+            match self.peek() {
+                Some(b' ' | b'\t' | b'\n' | b'\r') => self.pos += 1,
+                Some(b'/') if self.options.allow_comments => {
+                    let rest = &self.src[self.pos..];
+                    if rest.starts_with("//") {
+                        self.pos += rest.find('\n').unwrap_or(rest.len());
+                    } else if let Some(body) = rest.strip_prefix("/*") {
+                        match body.find("*/") {
+                            Some(len) => self.pos += len + 4,
+                            None => return self.error("Unterminated comment"),
+                        }
+                    } else {
+                        return Ok(());
+                    }
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn expect(&mut self, ch: u8) -> Result<(), ParseError> {
+        if self.peek() != Some(ch) {
+            return self.error(&format!("Expected '{}'", ch as char));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    /// Advances past a run of ASCII digits, returning how many there were.
+    fn skip_digits(&mut self) -> usize {
+        let start = self.pos;
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.pos += 1;
+        }
+        self.pos - start
+    }
+
+    /// Advances past the digits of a number in `radix`, and the `_` between
+    /// them if [`ParseOptions::allow_numeric_separators`] is set. Returns how
+    /// many bytes there were.
+    fn skip_number_digits(&mut self, radix: u32) -> Result<usize, ParseError> {
+        let start = self.pos;
+        while let Some(b) = self.peek() {
+            if b == b'_' && self.options.allow_numeric_separators {
+                let is_digit = |i: usize| self.src.as_bytes().get(i).is_some_and(|&d| char::from(d).is_digit(radix));
+                if self.pos == start || !is_digit(self.pos + 1) {
+                    return self.error("Numeric separator must be between digits");
+                }
+            } else if !char::from(b).is_digit(radix) {
+                break;
+            }
+            self.pos += 1;
+        }
+        Ok(self.pos - start)
+    }
+
+    /// Reads exactly `count` ASCII digits as a number.
+    fn read_digits(&mut self, count: usize) -> Result<u16, ParseError> {
+        let mut n = 0;
+        for _ in 0..count {
+            match self.peek() {
+                Some(d @ b'0'..=b'9') => n = n * 10 + u16::from(d - b'0'),
+                _ => return self.error("Expected digit"),
+            }
+            self.pos += 1;
+        }
+        Ok(n)
+    }
+
+    fn parse_document(&mut self) -> Result<RdnValue, ParseError> {
+        let value = self.parse_value()?;
+        self.skip_ws()?;
+        if self.pos < self.src.len() {
+            return self.error("Unexpected data after value");
+        }
+        Ok(value)
+    }
+
+    fn parse_partial_document(&mut self) -> Result<(RdnValue, Option<TruncationInfo>), ParseError> {
+        self.skip_ws()?;
+        let start = self.pos;
+        let value = match self.parse_value() {
+            Ok(value) => value,
+            Err(_) if self.cut_off_at(start) => return Ok((RdnValue::Null, Some(TruncationInfo { offset: start }))),
+            Err(e) => return Err(e),
+        };
+        if let Some(offset) = self.cut {
+            return Ok((value, Some(TruncationInfo { offset })));
+        }
+        self.skip_ws()?;
+        if self.pos < self.src.len() {
+            return self.error("Unexpected data after value");
+        }
+        Ok((value, None))
+    }
+
+    /// Whether the value at `start`, which failed to parse, was cut off by
+    /// the end of the input rather than malformed: a string, Binary literal
+    /// or RegExp without its closing quote or `/`, a prefix of `Map{` or
+    /// `Set{`, or another scalar with nothing after it.
+    fn cut_off_at(&self, start: usize) -> bool {
+        let rest = &self.src.as_bytes()[start..];
+        match rest {
+            [] => true,
+            [b'"', body @ ..] => {
+                let mut escaped = false;
+                for &b in body {
+                    match b {
+                        _ if escaped => escaped = false,
+                        b'\\' => escaped = true,
+                        b'"' => return false,
+                        _ if b < 0x20 => return false,
+                        _ => {}
+                    }
+                }
+                true
+            }
+            [b'b' | b'x'] => true,
+            [b'b' | b'x', b'"', payload @ ..] => !payload.contains(&b'"'),
+            [b'/', body @ ..] => {
+                let (mut escaped, mut in_class) = (false, false);
+                for &b in body {
+                    match b {
+                        _ if escaped => escaped = false,
+                        b'\\' => escaped = true,
+                        b'[' => in_class = true,
+                        b']' => in_class = false,
+                        b'/' if !in_class => return false,
+                        _ => {}
+                    }
+                }
+                true
+            }
+            [b'M', ..] => b"Map{".starts_with(rest) && rest.len() < 4,
+            [b'S', ..] => b"Set{".starts_with(rest) && rest.len() < 4,
+            [b'-' | b'0'..=b'9' | b't' | b'f' | b'n' | b'N' | b'I' | b'@', ..] => {
+                !rest.iter().any(|&b| matches!(b, b' ' | b'\t' | b'\n' | b'\r' | b',' | b']' | b'}' | b')' | b'='))
+            }
+            _ => false,
+        }
+    }
+
+    /// Parses an element, key or value inside a container. In partial mode,
+    /// returns `None` if it is dropped for being cut off by the end of the
+    /// input, recording the cut at its start; see [`parse_partial`].
+    fn parse_part(&mut self) -> Result<Option<RdnValue>, ParseError> {
+        if !self.partial {
+            return self.parse_value().map(Some);
+        }
+        self.skip_ws()?;
+        let start = self.pos;
+        let value = match self.parse_value() {
+            Ok(value) => value,
+            Err(_) if self.cut_off_at(start) => {
+                self.cut = Some(start);
+                return Ok(None);
+            }
+            Err(e) => return Err(e),
+        };
+        // A container cut off inside is kept with what it has. Anything else
+        // the input ends right after is dropped unless a quote or bracket
+        // closes it, since more input could have changed it.
+        let closed = matches!(self.src.as_bytes()[self.pos - 1], b'"' | b']' | b'}' | b')');
+        if self.cut.is_none() && self.pos == self.src.len() && !closed {
+            self.cut = Some(start);
+            return Ok(None);
+        }
+        Ok(Some(value))
+    }
+
+    /// Skips whitespace, then in partial mode returns whether the input has
+    /// been cut short: whether it ends there, or a cut has already been
+    /// recorded further in.
+    fn at_cut(&mut self) -> Result<bool, ParseError> {
+        self.skip_ws()?;
+        if self.partial && self.cut.is_none() && self.pos == self.src.len() {
+            self.cut = Some(self.pos);
+        }
+        Ok(self.cut.is_some())
+    }
+
+    /// Records `span` for a value, taking the spans recorded since there were
+    /// `mark` as its children.
+    fn record_span(&mut self, span: Range<usize>, mark: usize) {
+        if let Some(spans) = &mut self.spans {
+            let children = spans.split_off(mark);
+            spans.push(SpanTree { span, children });
+        }
+    }
+
+    fn span_mark(&self) -> usize {
+        self.spans.as_ref().map_or(0, Vec::len)
+    }
+
+    fn parse_value(&mut self) -> Result<RdnValue, ParseError> {
+        self.skip_ws()?;
+        let start = self.pos;
+        let mark = self.span_mark();
+        let value = self.parse_value_inner()?;
+        self.record_span(start..self.pos, mark);
+        Ok(value)
+    }
+
+    fn parse_value_inner(&mut self) -> Result<RdnValue, ParseError> {
+        let Some(ch) = self.peek() else {
+            return self.error("Unexpected end of input");
+        };
+        if matches!(ch, b'[' | b'{' | b'(' | b'M' | b'S') {
+            if self.depth == self.options.max_depth {
+                return self.error(&format!("Maximum nesting depth of {} exceeded", self.options.max_depth));
+            }
+            self.depth += 1;
+            let value = self.parse_container(ch);
+            self.depth -= 1;
+            return value;
+        }
+        if self.options.infinity_aliases && matches!(ch, b'+' | b'-' | b'i' | b'I') {
+            if let Some(value) = self.parse_infinity_alias() {
+                return Ok(value);
+            }
+        }
+        match ch {
+            b'"' => self.parse_string().map(RdnValue::String),
+            b'\'' if self.options.allow_single_quotes => self.parse_string().map(RdnValue::String),
+            b'-' | b'0'..=b'9' => self.parse_number(),
+            b't' => self.parse_literal("true", RdnValue::Bool(true)),
+            b'f' => self.parse_literal("false", RdnValue::Bool(false)),
+            b'n' => self.parse_literal("null", RdnValue::Null),
+            b'@' => self.parse_at(),
+            b'/' => self.parse_regexp(),
+            b'b' => self.parse_base64(),
+            b'x' => self.parse_hex(),
+            b'z' if self.options.allow_base32_literals => self.parse_base32(),
+            b'N' => self.parse_literal("NaN", RdnValue::Number(f64::NAN)),
+            b'I' => self.parse_literal("Infinity", RdnValue::Number(f64::INFINITY)),
+            _ => {
+                let c = self.src[self.pos..].chars().next().unwrap_or_default();
+                self.error(&format!("Unexpected character '{c}'"))
+            }
+        }
+    }
+
+    fn parse_container(&mut self, open: u8) -> Result<RdnValue, ParseError> {
+        match open {
+            b'[' => self.parse_array(),
+            b'{' => self.parse_brace(),
+            b'M' => self.parse_map(),
+            b'S' => self.parse_set(),
+            _ => self.parse_tuple(),
+        }
+    }
+
+    fn parse_literal(&mut self, word: &str, value: RdnValue) -> Result<RdnValue, ParseError> {
+        if !self.src[self.pos..].starts_with(word) {
+            return self.error(&format!("Expected '{word}'"));
+        }
+        self.pos += word.len();
+        Ok(value)
+    }
+
+    /// Reads an infinity spelled any way [`ParseOptions::infinity_aliases`]
+    /// allows, if one is next. Otherwise leaves the cursor where it was.
+    fn parse_infinity_alias(&mut self) -> Option<RdnValue> {
+        let rest = &self.src[self.pos..];
+        let (sign_len, infinity) = match rest.as_bytes()[0] {
+            b'+' => (1, f64::INFINITY),
+            b'-' => (1, f64::NEG_INFINITY),
+            _ => (0, f64::INFINITY),
+        };
+        // `Infinity` first, since it starts with `Inf`.
+        let word = ["Infinity", "inf", "Inf"].into_iter().find(|word| rest[sign_len..].starts_with(word))?;
+        self.pos += sign_len + word.len();
+        Some(RdnValue::Number(infinity))
+    }
+
+    // --- Strings ---
+
+    /// Whether the next byte opens a string.
+    fn at_string(&self) -> bool {
+        match self.peek() {
+            Some(b'"') => true,
+            Some(b'\'') => self.options.allow_single_quotes,
+            _ => false,
+        }
+    }
+
+    /// Parses a string opened by the quote at the cursor, `"` or `'`.
+    fn parse_string(&mut self) -> Result<String, ParseError> {
+        let start = self.pos;
+        let quote = self.src.as_bytes()[start];
+        self.pos += 1;
+        let mut out = String::new();
+        loop {
+            // Copy the run of plain characters up to the closing quote, a
+            // backslash, or a control character. All three are ASCII, so the
+            // slice always ends on a char boundary.
+            let run = self.pos;
+            while let Some(b) = self.peek() {
+                if b == quote || b == b'\\' || b < 0x20 {
+                    break;
+                }
+                self.pos += 1;
+            }
+            self.check_string_len(start, out.len() + (self.pos - run))?;
+            out.push_str(&self.src[run..self.pos]);
+            match self.peek() {
+                None => return self.error("Unterminated string"),
+                Some(b) if b == quote => {
+                    self.pos += 1;
+                    return Ok(out);
+                }
+                Some(b'\\') => {
+                    self.pos += 1;
+                    self.parse_escape(&mut out)?;
+                    self.check_string_len(start, out.len())?;
+                }
+                Some(_) => return self.error("Unescaped control character in string"),
+            }
+        }
+    }
+
+    /// Fails if a string opened at `start` has grown to `len` bytes, past
+    /// [`ParseOptions::max_string_len`].
+    fn check_string_len(&self, start: usize, len: usize) -> Result<(), ParseError> {
+        if len > self.options.max_string_len {
+            let message = format!("String exceeds maximum length of {} bytes", self.options.max_string_len);
+            return Err(ParseError::new(self.src.as_bytes(), start, &message));
+        }
+        Ok(())
+    }
+
+    /// Decodes the escape after a backslash and appends it to `out`.
+    fn parse_escape(&mut self, out: &mut String) -> Result<(), ParseError> {
+        let decoded = match self.peek() {
+            None => return self.error("Unterminated string"),
+            Some(b'"') => '"',
+            Some(b'\'') if self.options.allow_single_quotes => '\'',
+            Some(b'\\') => '\\',
+            Some(b'/') => '/',
+            // A digit after `\0` would make a legacy octal escape.
+            Some(b'0')
+                if !self.options.strict_json_escapes
+                    && !self.src.as_bytes().get(self.pos + 1).is_some_and(u8::is_ascii_digit) =>
+            {
+                '\0'
+            }
+            Some(b'b') => '\u{08}',
+            Some(b'f') => '\u{0C}',
+            Some(b'n') => '\n',
+            Some(b'r') => '\r',
+            Some(b't') => '\t',
+            Some(b'u') => {
+                self.pos += 1;
+                return self.parse_unicode_escape(out);
+            }
+            Some(_) => {
+                let c = self.src[self.pos..].chars().next().unwrap_or_default();
+                return self.error(&format!("Invalid escape sequence '\\{c}'"));
+            }
+        };
+        self.pos += 1;
+        out.push(decoded);
+        Ok(())
+    }
+
+    /// Decodes the `XXXX` of a `\uXXXX` escape, combining a UTF-16 surrogate
+    /// pair spelled as two consecutive escapes into one character.
+    fn parse_unicode_escape(&mut self, out: &mut String) -> Result<(), ParseError> {
+        let unit = self.parse_hex4()?;
+        let code = match unit {
+            0xD800..=0xDBFF => match self.low_surrogate_escape() {
+                Some(low) => {
+                    self.pos += 6;
+                    0x10000 + ((unit - 0xD800) << 10) + (low - 0xDC00)
+                }
+                None => return self.lone_surrogate(out),
+            },
+            0xDC00..=0xDFFF => return self.lone_surrogate(out),
+            _ => unit,
+        };
+        // Surrogates are excluded above, so every remaining code is a scalar value.
+        out.push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+        Ok(())
+    }
+
+    /// Returns the low surrogate spelled by a `\uXXXX` escape at the cursor,
+    /// if there is one, without moving the cursor.
+    fn low_surrogate_escape(&self) -> Option<u32> {
+        let hex = self.src.get(self.pos..self.pos + 6)?.strip_prefix("\\u")?;
+        if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        u32::from_str_radix(hex, 16).ok().filter(|unit| (0xDC00..=0xDFFF).contains(unit))
+    }
+
+    /// Handles a surrogate escape that is not half of a pair, as
+    /// [`ParseOptions::lone_surrogates`] says.
+    fn lone_surrogate(&self, out: &mut String) -> Result<(), ParseError> {
+        match self.options.lone_surrogates {
+            LoneSurrogates::Error => self.error("Unpaired surrogate in unicode escape"),
+            LoneSurrogates::ReplacementChar => {
+                out.push(char::REPLACEMENT_CHARACTER);
+                Ok(())
+            }
+        }
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32, ParseError> {
+        match self.src.get(self.pos..self.pos + 4) {
+            Some(hex) if hex.bytes().all(|b| b.is_ascii_hexdigit()) => {
+                self.pos += 4;
+                Ok(u32::from_str_radix(hex, 16).unwrap_or_default())
+            }
+            _ => self.error("Invalid unicode escape"),
+        }
+    }
+
+    // --- Numbers ---
+
+    fn parse_number(&mut self) -> Result<RdnValue, ParseError> {
+        let start = self.pos;
+        if self.peek() == Some(b'-') {
+            self.pos += 1;
+        }
+        match self.peek() {
+            // There is no `+Infinity`, just as JSON numbers take no `+` sign.
+            Some(b'I') => return self.parse_literal("Infinity", RdnValue::Number(f64::NEG_INFINITY)),
+            Some(b'0') if self.options.allow_radix_literals => {
+                let radix = match self.src.as_bytes().get(self.pos + 1) {
+                    Some(b'x' | b'X') => 16,
+                    Some(b'o' | b'O') => 8,
+                    Some(b'b' | b'B') => 2,
+                    _ => 0,
+                };
+                if radix != 0 {
+                    return self.parse_radix_number(start, radix);
+                }
+                self.pos += 1;
+                if let Some(b'0'..=b'9') = self.peek() {
+                    return self.error("Leading zeros not allowed");
+                }
+            }
+            Some(b'0') => {
+                self.pos += 1;
+                if let Some(b'0'..=b'9') = self.peek() {
+                    return self.error("Leading zeros not allowed");
+                }
+            }
+            Some(b'1'..=b'9') => {
+                self.skip_number_digits(10)?;
+            }
+            _ => return self.error("Expected digit"),
+        }
+        let has_fraction = self.peek() == Some(b'.');
+        if has_fraction {
+            self.pos += 1;
+            if self.skip_number_digits(10)? == 0 {
+                return self.error("Expected digit after decimal point");
+            }
+        }
+        let has_exponent = matches!(self.peek(), Some(b'e' | b'E'));
+        if has_exponent {
+            self.pos += 1;
+            if let Some(b'+' | b'-') = self.peek() {
+                self.pos += 1;
+            }
+            if self.skip_number_digits(10)? == 0 {
+                return self.error("Expected digit in exponent");
+            }
+        }
+        let text = &self.src[start..self.pos].replace('_', "");
+        if !self.options.unit_suffixes.is_empty() {
+            if let Some(value) = self.parse_unit_suffix(text)? {
+                return Ok(value);
+            }
+        }
+        match self.peek() {
+            Some(b'n') => {
+                if has_fraction || has_exponent {
+                    return self.error("BigInt cannot have decimal point or exponent");
+                }
+                self.pos += 1;
+                return BigInt::new(text).map(RdnValue::BigInt).or_else(|e| self.error(&e));
+            }
+            Some(b'm') if self.options.allow_decimal_literals => {
+                if has_exponent {
+                    return self.error("Decimal cannot have an exponent");
+                }
+                self.pos += 1;
+                return Decimal::new(text).map(RdnValue::Decimal).or_else(|e| self.error(&e));
+            }
+            _ => {}
+        }
+        match text.parse() {
+            Ok(n) => Ok(RdnValue::Number(n)),
+            Err(_) => self.error(&format!("Invalid number '{text}'")),
+        }
+    }
+
+    /// Parses the rest of a `0x`, `0o` or `0b` integer that began at `start`,
+    /// with the cursor on its `0`.
+    fn parse_radix_number(&mut self, start: usize, radix: u32) -> Result<RdnValue, ParseError> {
+        self.pos += 2;
+        let digits_start = self.pos;
+        if !self.peek().is_some_and(|b| char::from(b).is_digit(radix)) {
+            let prefix = &self.src[self.pos - 2..self.pos];
+            return self.error(&format!("Expected digit after '{prefix}'"));
+        }
+        self.skip_number_digits(radix)?;
+        let digits = self.src[digits_start..self.pos].replace('_', "");
+        let negative = self.src.as_bytes()[start] == b'-';
+        if self.peek() == Some(b'n') {
+            if digits.len() > ParseOptions::MAX_RADIX_BIGINT_DIGITS {
+                self.pos = start;
+                let max = ParseOptions::MAX_RADIX_BIGINT_DIGITS;
+                return self.error(&format!("BigInt literal has more than {max} digits"));
+            }
+            self.pos += 1;
+            let mut decimal = radix_to_decimal(&digits, radix);
+            if negative && decimal != "0" {
+                decimal.insert(0, '-');
+            }
+            return BigInt::new(&decimal).map(RdnValue::BigInt).or_else(|e| self.error(&e));
+        }
+        let n = radix_to_f64(&digits, radix);
+        // Keep the sign of `-0x0`, as JavaScript does.
+        Ok(RdnValue::Number(if negative { -n } else { n }))
+    }
+
+    /// Applies a unit suffix from [`ParseOptions::unit_suffixes`] to the
+    /// number `text`. Returns `None` if there is no suffix, or it is a BigInt
+    /// or Decimal marker left for the caller.
+    fn parse_unit_suffix(&mut self, text: &str) -> Result<Option<RdnValue>, ParseError> {
+        let rest = &self.src[self.pos..];
+        let suffix = &rest[..rest.bytes().take_while(|b| b.is_ascii_alphabetic() || *b == b'%').count()];
+        match self.options.unit_suffixes.iter().find(|(s, _)| s == suffix) {
+            Some(&(_, factor)) => {
+                let n: f64 = match text.parse() {
+                    Ok(n) => n,
+                    Err(_) => return self.error(&format!("Invalid number '{text}'")),
+                };
+                self.pos += suffix.len();
+                Ok(Some(RdnValue::Number(n * factor)))
+            }
+            None if matches!(suffix, "" | "n") => Ok(None),
+            None if suffix == "m" && self.options.allow_decimal_literals => Ok(None),
+            None => self.error(&format!("Unknown unit suffix '{suffix}'")),
+        }
+    }
+
+    // --- @ literals ---
+
+    fn parse_at(&mut self) -> Result<RdnValue, ParseError> {
+        self.pos += 1; // @
+        match self.src.as_bytes()[self.pos..] {
+            // A '-' after four digits starts a date: @2024-01-15...
+            [b'0'..=b'9', _, _, _, b'-', ..] => self.parse_date(),
+            // ...as does a sign, for an expanded year: @+010000-01-01
+            [b'+' | b'-', b'0'..=b'9', ..] => self.parse_date(),
+            // A ':' after two digits starts a time of day: @14:30:00
+            [b'0'..=b'9', _, b':', ..] => self.parse_time_only(),
+            [b'0'..=b'9', ..] => self.parse_unix_timestamp(),
+            [b'P', ..] => self.parse_duration(),
+            _ => self.error("Invalid @ literal"),
+        }
+    }
+
+    /// Parses an ISO 8601 date or date-time after the `@`.
+    fn parse_date(&mut self) -> Result<RdnValue, ParseError> {
+        let start = self.pos;
+        while let Some(b) = self.peek() {
+            if !(b.is_ascii_alphanumeric() || matches!(b, b'-' | b':' | b'.' | b'+')) {
+                break;
+            }
+            self.pos += 1;
+        }
+        match parse_iso_datetime(&self.src[start..self.pos]) {
+            Ok(millis) => Ok(RdnValue::Date(RdnDate { millis })),
+            Err(e) => {
+                self.pos = start;
+                self.error(&format!("Invalid date: {e}"))
+            }
+        }
+    }
+
+    /// Parses `HH:mm:ss[.mmm]` after the `@`.
+    fn parse_time_only(&mut self) -> Result<RdnValue, ParseError> {
+        let start = self.pos;
+        let hours = self.read_digits(2)?;
+        self.expect(b':')?;
+        let minutes = self.read_digits(2)?;
+        self.expect(b':')?;
+        let seconds = self.read_digits(2)?;
+        let mut milliseconds = 0;
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            milliseconds = self.read_digits(3)?;
+        }
+        // Two digits always fit in a u8.
+        match RdnTimeOnly::new(hours as u8, minutes as u8, seconds as u8, milliseconds) {
+            Ok(time) => Ok(RdnValue::TimeOnly(time)),
+            Err(e) => {
+                self.pos = start;
+                self.error(&format!("Invalid time: {e}"))
+            }
+        }
+    }
+
+    /// Parses a Unix timestamp after the `@`. Like the JS implementation, up
+    /// to 10 digits are seconds and anything longer is milliseconds, so
+    /// `@1705312200` and `@1705312200000` are the same instant. A timestamp
+    /// past the last instant a Date can be written as is an error.
+    fn parse_unix_timestamp(&mut self) -> Result<RdnValue, ParseError> {
+        let start = self.pos;
+        let digits = self.skip_digits();
+        // A run of ASCII digits always parses as an f64.
+        let n: f64 = self.src[start..self.pos].parse().unwrap_or(f64::NAN);
+        let millis = if digits <= 10 { n * 1000.0 } else { n };
+        if millis > MAX_DATE_MILLIS as f64 {
+            self.pos = start;
+            return self.error("timestamp out of range");
+        }
+        Ok(RdnValue::Date(RdnDate { millis }))
+    }
+
+    /// Parses an ISO 8601 duration such as `P1Y2M3DT4H5M6S` after the `@`.
+    fn parse_duration(&mut self) -> Result<RdnValue, ParseError> {
+        let start = self.pos;
+        self.pos += 1; // P
+        while let Some(b'0'..=b'9' | b'.' | b'Y' | b'M' | b'W' | b'D' | b'T' | b'H' | b'S') = self.peek() {
+            self.pos += 1;
+        }
+        match RdnDuration::new(&self.src[start..self.pos]) {
+            Ok(duration) => Ok(RdnValue::Duration(duration)),
+            Err(e) => {
+                self.pos = start;
+                self.error(&format!("Invalid duration: {e}"))
+            }
+        }
+    }
+
+    // --- RegExp ---
+
+    /// Parses `/pattern/flags`. The pattern is kept verbatim, escapes
+    /// included; a backslash skips the next character, so `\/` does not end
+    /// the pattern.
+    fn parse_regexp(&mut self) -> Result<RdnValue, ParseError> {
+        let start = self.pos;
+        self.pos += 1; // /
+        let pattern_start = self.pos;
+        // As in JavaScript, a `/` inside a character class does not end the
+        // pattern.
+        let mut in_class = false;
+        loop {
+            match self.peek() {
+                None => {
+                    self.pos = start;
+                    return self.error("Unterminated regular expression");
+                }
+                Some(b'/') if !in_class => break,
+                Some(b'\\') => self.pos += 2,
+                Some(b) => {
+                    match b {
+                        b'[' => in_class = true,
+                        b']' => in_class = false,
+                        _ => {}
+                    }
+                    self.pos += 1;
+                }
+            }
+        }
+        let pattern = &self.src[pattern_start..self.pos];
+        if pattern.is_empty() {
+            self.pos = start;
+            return self.error("Empty regular expression");
+        }
+        self.pos += 1; // /
+        let flags_start = self.pos;
+        while self.peek().is_some_and(|b| b.is_ascii_alphabetic()) {
+            self.pos += 1;
+        }
+        match RdnRegExp::new(pattern, &self.src[flags_start..self.pos]) {
+            Ok(re) => Ok(RdnValue::RegExp(re)),
+            Err(e) => {
+                self.pos = flags_start;
+                self.error(&format!("Invalid regular expression: {e}"))
+            }
+        }
+    }
+
+    // --- Binary ---
+
+    /// Reads the quoted payload of a binary literal, after its one-letter
+    /// `prefix`. The payload has no escapes, so it simply runs to the next `"`.
+    fn binary_payload(&mut self, prefix: char) -> Result<&str, ParseError> {
+        let start = self.pos;
+        self.pos += 1; // prefix
+        if self.peek() != Some(b'"') {
+            return self.error(&format!("Expected '\"' after '{prefix}'"));
+        }
+        self.pos += 1;
+        let Some(len) = self.src[self.pos..].find('"') else {
+            self.pos = start;
+            return self.error("Unterminated binary literal");
+        };
+        let payload = &self.src[self.pos..self.pos + len];
+        self.pos += len + 1;
+        Ok(payload)
+    }
+
+    /// Parses `b"..."`, strict RFC 4648 base64.
+    fn parse_base64(&mut self) -> Result<RdnValue, ParseError> {
+        let start = self.pos;
+        let payload = self.binary_payload('b')?;
+        match decode_base64(payload) {
+            Ok(bytes) => Ok(RdnValue::Binary(bytes)),
+            Err(e) => {
+                self.pos = start;
+                self.error(&format!("Invalid binary literal: {e}"))
+            }
+        }
+    }
+
+    /// Parses `x"..."`, two hex digits per byte.
+    fn parse_hex(&mut self) -> Result<RdnValue, ParseError> {
+        let start = self.pos;
+        let payload = self.binary_payload('x')?;
+        match decode_hex(payload) {
+            Ok(bytes) => Ok(RdnValue::Binary(bytes)),
+            Err(e) => {
+                self.pos = start;
+                self.error(&format!("Invalid binary literal: {e}"))
+            }
+        }
+    }
+
+    /// Parses `z"..."`, strict RFC 4648 base32.
+    fn parse_base32(&mut self) -> Result<RdnValue, ParseError> {
+        let start = self.pos;
+        let payload = self.binary_payload('z')?;
+        match decode_base32(payload) {
+            Ok(bytes) => Ok(RdnValue::Binary(bytes)),
+            Err(e) => {
+                self.pos = start;
+                self.error(&format!("Invalid binary literal: {e}"))
+            }
+        }
+    }
+
+    // --- Containers ---
+
+    fn parse_array(&mut self) -> Result<RdnValue, ParseError> {
+        self.pos += 1; // [
+        self.parse_elements(b']').map(RdnValue::Array)
+    }
+
+    /// Parses `(a, b, ...)`. A tuple is just another spelling of an Array:
+    /// parentheses never group, so `(1)` is a one-element Array and no
+    /// trailing comma is needed (or allowed) to force one.
+    fn parse_tuple(&mut self) -> Result<RdnValue, ParseError> {
+        self.pos += 1; // (
+        self.parse_elements(b')').map(RdnValue::Array)
+    }
+
+    /// Parses comma-separated values up to and including `close`, after the
+    /// opening bracket.
+    fn parse_elements(&mut self, close: u8) -> Result<Vec<RdnValue>, ParseError> {
+        let mut items = Vec::new();
+        if self.at_cut()? {
+            return Ok(items);
+        }
+        if self.peek() == Some(close) {
+            self.pos += 1;
+            return Ok(items);
+        }
+        loop {
+            match self.parse_part()? {
+                Some(item) => items.push(item),
+                None => return Ok(items),
+            }
+            if self.at_cut()? {
+                return Ok(items);
+            }
+            if self.peek() != Some(b',') {
+                break;
+            }
+            self.pos += 1;
+            if self.at_cut()? {
+                return Ok(items);
+            }
+        }
+        self.expect(close)?;
+        Ok(items)
+    }
+
+    /// Parses a bare `{...}`, which may be an Object, a Map, or a Set.
+    ///
+    /// `{}` is an empty Object. Otherwise the first value is read and the
+    /// separator after it decides: `:` makes an Object (and the value must
+    /// be a string), `=>` a Map, and `,` or `}` a Set, so `{"a"}` is a
+    /// one-element Set.
+    fn parse_brace(&mut self) -> Result<RdnValue, ParseError> {
+        self.pos += 1; // {
+        if self.at_cut()? {
+            return Ok(RdnValue::Object(Vec::new()));
+        }
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(RdnValue::Object(Vec::new()));
+        }
+        let first_start = self.pos;
+        if let Some(key) = self.parse_unquoted_key()? {
+            return self.finish_object(key, first_start);
+        }
+        // Until the separator, it is not known what the first value is part
+        // of, so it is dropped if the input ends first.
+        let first = match self.parse_part()? {
+            Some(first) if !self.at_cut()? => first,
+            _ => {
+                self.cut = Some(first_start);
+                return Ok(RdnValue::Object(Vec::new()));
+            }
+        };
+        match self.peek() {
+            Some(b':') => match first {
+                RdnValue::String(key) => self.finish_object(key, first_start),
+                _ => {
+                    self.pos = first_start;
+                    self.error("Object key must be a string")
+                }
+            },
+            Some(b'=') => self.finish_map(first, first_start),
+            Some(b',' | b'}') => self.finish_set(first),
+            None => self.error("Unterminated brace expression"),
+            Some(_) => self.error("Expected ':', '=>', ',' or '}' after value in brace expression"),
+        }
+    }
+
+    /// Reads a bare identifier key if [`ParseOptions::allow_unquoted_keys`] is
+    /// set and one is next, followed by a `:`. Otherwise leaves the cursor
+    /// where it was.
+    fn parse_unquoted_key(&mut self) -> Result<Option<String>, ParseError> {
+        if !self.options.allow_unquoted_keys {
+            return Ok(None);
+        }
+        let start = self.pos;
+        let rest = &self.src.as_bytes()[start..];
+        if !rest.first().is_some_and(|&b| is_identifier_start(b)) {
+            return Ok(None);
+        }
+        self.pos += rest.iter().take_while(|&&b| is_identifier_continue(b)).count();
+        let end = self.pos;
+        self.skip_ws()?;
+        if self.peek() != Some(b':') {
+            self.pos = start;
+            return Ok(None);
+        }
+        self.check_string_len(start, end - start)?;
+        self.record_span(start..end, self.span_mark());
+        Ok(Some(self.src[start..end].to_string()))
+    }
+
+    /// Parses the rest of an Object whose first key, which began at
+    /// `key_start`, has been read.
+    fn finish_object(&mut self, first_key: String, key_start: usize) -> Result<RdnValue, ParseError> {
+        let mut entries = Vec::new();
+        let (mut key, mut key_start) = (first_key, key_start);
+        loop {
+            if self.at_cut()? {
+                self.cut = Some(key_start);
+                break;
+            }
+            self.expect(b':')?;
+            match self.parse_part()? {
+                Some(value) => entries.push((key, value)),
+                None => self.cut = Some(key_start),
+            }
+            if self.at_cut()? || self.peek() != Some(b',') {
+                break;
+            }
+            self.pos += 1;
+            if self.at_cut()? {
+                break;
+            }
+            key_start = self.pos;
+            key = match self.parse_unquoted_key()? {
+                Some(key) => key,
+                None if self.at_string() => {
+                    let (start, mark) = (self.pos, self.span_mark());
+                    match self.parse_string() {
+                        Ok(key) => {
+                            self.record_span(start..self.pos, mark);
+                            key
+                        }
+                        Err(_) if self.partial && self.cut_off_at(start) => {
+                            self.cut = Some(start);
+                            break;
+                        }
+                        Err(e) => return Err(e),
+                    }
+                }
+                None if self.partial && self.cut_off_at(key_start) => {
+                    self.cut = Some(key_start);
+                    break;
+                }
+                None => return self.error("Object key must be a string"),
+            };
+        }
+        if self.cut.is_none() {
+            self.expect(b'}')?;
+        }
+        Ok(RdnValue::Object(entries))
+    }
+
+    /// Parses the rest of a Map whose first key, which began at `key_start`,
+    /// has been read.
+    fn finish_map(&mut self, first_key: RdnValue, key_start: usize) -> Result<RdnValue, ParseError> {
+        let mut entries = Vec::new();
+        let (mut key, mut key_start) = (first_key, key_start);
+        loop {
+            // A key cut off inside has no value to go with it.
+            if self.at_cut()? || (self.partial && self.src[self.pos..] == *"=") {
+                self.cut = Some(key_start);
+                break;
+            }
+            if !self.src[self.pos..].starts_with("=>") {
+                return self.error("Expected '=>' in map entry");
+            }
+            self.pos += 2;
+            match self.parse_part()? {
+                Some(value) => entries.push((key, value)),
+                None => self.cut = Some(key_start),
+            }
+            if self.at_cut()? || self.peek() != Some(b',') {
+                break;
+            }
+            self.pos += 1;
+            if self.at_cut()? {
+                break;
+            }
+            key_start = self.pos;
+            match self.parse_part()? {
+                Some(next) => key = next,
+                None => break,
+            }
+        }
+        if self.cut.is_none() {
+            self.expect(b'}')?;
+        }
+        Ok(RdnValue::Map(entries))
+    }
+
+    /// Parses the rest of a Set whose first member has been read.
+    fn finish_set(&mut self, first: RdnValue) -> Result<RdnValue, ParseError> {
+        let mut items = vec![first];
+        while !self.at_cut()? && self.peek() == Some(b',') {
+            self.pos += 1;
+            if self.at_cut()? {
+                break;
+            }
+            match self.parse_part()? {
+                Some(item) => items.push(item),
+                None => break,
+            }
+        }
+        if self.cut.is_none() {
+            self.expect(b'}')?;
+        }
+        Ok(RdnValue::Set(items))
+    }
+
+    /// Parses `Map{k => v, ...}`. Keys may be any value, and duplicate keys
+    /// are kept as written; see [`RdnValue::deduplicate_sets`].
+    fn parse_map(&mut self) -> Result<RdnValue, ParseError> {
+        if !self.src[self.pos..].starts_with("Map{") {
+            return self.error("Expected 'Map{'");
+        }
+        self.pos += 4;
+        if self.at_cut()? {
+            return Ok(RdnValue::Map(Vec::new()));
+        }
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(RdnValue::Map(Vec::new()));
+        }
+        let key_start = self.pos;
+        match self.parse_part()? {
+            Some(key) => self.finish_map(key, key_start),
+            None => Ok(RdnValue::Map(Vec::new())),
+        }
+    }
+
+    /// Parses `Set{v, ...}`. Duplicate members are kept as written; see
+    /// [`RdnValue::deduplicate_sets`].
+    fn parse_set(&mut self) -> Result<RdnValue, ParseError> {
+        if !self.src[self.pos..].starts_with("Set{") {
+            return self.error("Expected 'Set{'");
+        }
+        self.pos += 4;
+        self.parse_elements(b'}').map(RdnValue::Set)
+    }
+}
+
+/// Converts ASCII `digits` in `radix` (2, 8 or 16) to a decimal string.
+fn radix_to_decimal(digits: &str, radix: u32) -> String {
+    const LIMB: u64 = 1_000_000_000;
+    // As many digits as fit in 28 bits are folded in per pass, so each limb
+    // times the multiplier stays well inside a u64.
+    let per_chunk = (28 / radix.trailing_zeros()) as usize;
+    // Little-endian base 10^9 limbs, multiplied up as each chunk comes in.
+    let mut limbs = vec![0u64];
+    for chunk in digits.as_bytes().chunks(per_chunk) {
+        let mut carry = 0;
+        for &b in chunk {
+            carry = carry * u64::from(radix) + u64::from(char::from(b).to_digit(radix).unwrap_or_default());
+        }
+        let multiplier = u64::from(radix).pow(chunk.len() as u32);
+        for limb in &mut limbs {
+            let v = *limb * multiplier + carry;
+            *limb = v % LIMB;
+            carry = v / LIMB;
+        }
+        while carry > 0 {
+            limbs.push(carry % LIMB);
+            carry /= LIMB;
+        }
+    }
+    while limbs.len() > 1 && limbs.last() == Some(&0) {
+        limbs.pop();
+    }
+    let mut decimal = limbs.last().map(u64::to_string).unwrap_or_default();
+    for limb in limbs.iter().rev().skip(1) {
+        decimal.push_str(&format!("{limb:09}"));
+    }
+    decimal
+}
+
+/// Converts ASCII `digits` in `radix` (2, 8 or 16) to the nearest `f64`, in
+/// time linear in their length.
+fn radix_to_f64(digits: &str, radix: u32) -> f64 {
+    let bits_per_digit = radix.trailing_zeros();
+    // The leading 64 significant bits, how many significant bits there are in
+    // all, and whether any bit past the first 64 is set. Folding that last
+    // into the lowest kept bit makes the conversion to f64 round as if every
+    // bit were there.
+    let (mut top, mut len, mut sticky) = (0u64, 0u64, false);
+    for c in digits.chars() {
+        let digit = u64::from(c.to_digit(radix).unwrap_or_default());
+        for i in (0..bits_per_digit).rev() {
+            let bit = digit >> i & 1;
+            if len == 0 && bit == 0 {
+                continue;
+            }
+            if len < 64 {
+                top = top << 1 | bit;
+            } else {
+                sticky |= bit == 1;
+            }
+            len += 1;
+        }
+    }
+    if len <= 64 {
+        return top as f64;
+    }
+    // Past 2^1100 the result is infinite whatever the leading bits.
+    let scale = (len - 64).min(1100) as i32;
+    (top | u64::from(sticky)) as f64 * 2f64.powi(scale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{n, obj, s};
+
+    // --- ParseError tests ---
+
+    #[test]
+    fn parse_error_reports_line_and_column() {
+        let err = parse("{\n  \"a\": 1,\n  \"b\": ]\n}").unwrap_err();
+        assert_eq!(err.message(), "Unexpected character ']'");
+        assert_eq!(err.offset(), 19);
+        assert_eq!((err.line(), err.column()), (3, 8));
+        assert_eq!(err.to_string(), "Unexpected character ']' at position 19");
+    }
+
+    #[test]
+    fn parse_error_column_counts_characters() {
+        let err = parse("[\r\n  \"\u{e9}\u{1F600}\" x]").unwrap_err();
+        assert_eq!(err.offset(), 14);
+        assert_eq!((err.line(), err.column()), (2, 8));
+        let err = parse("").unwrap_err();
+        assert_eq!((err.offset(), err.line(), err.column()), (0, 1, 1));
+    }
+
+    #[test]
+    fn parse_error_points_at_token_start() {
+        let err = parse("[1,\n @2024-13-01]").unwrap_err();
+        assert_eq!((err.offset(), err.line(), err.column()), (6, 2, 3));
+        let err: Box<dyn Error> = Box::new(err);
+        assert!(err.to_string().starts_with("Invalid date: "));
+    }
+
+    // --- Comment tests ---
+
+    fn lenient() -> ParseOptions {
+        ParseOptions { allow_comments: true, ..ParseOptions::default() }
+    }
+
+    #[test]
+    fn comments_are_rejected_by_default() {
+        assert_eq!(parse("// note\n1").unwrap_err().to_string(), "Empty regular expression at position 0");
+        assert!(parse("[1 /* two */]").is_err());
+    }
+
+    #[test]
+    fn comments_are_whitespace() {
+        let input = "// config\n{\n  \"a\": 1, // first\n  /* the\n second */ \"b\": /*x*/ 2\n}// end";
+        assert_eq!(parse_with(input, &lenient()).unwrap(), obj(vec![("a", n(1.0)), ("b", n(2.0))]));
+        assert_eq!(parse_with("Map{1 /**/ => 2}", &lenient()).unwrap(), RdnValue::Map(vec![(n(1.0), n(2.0))]));
+    }
+
+    #[test]
+    fn comments_alongside_regexps() {
+        let re = |p: &str, f: &str| RdnValue::RegExp(RdnRegExp::new(p, f).unwrap());
+        let input = "[/a\\/\\/b/g, // trailing /x/\n /* /y/ */ /\\/*/, /c/]";
+        let expected = vec![re(r"a\/\/b", "g"), re(r"\/*", ""), re("c", "")];
+        assert_eq!(parse_with(input, &lenient()).unwrap(), RdnValue::Array(expected));
+    }
+
+    #[test]
+    fn comment_markers_inside_strings() {
+        let input = r#"["// not a comment", "/* nor */"]"#;
+        assert_eq!(parse_with(input, &lenient()).unwrap(), RdnValue::Array(vec![s("// not a comment"), s("/* nor */")]));
+    }
+
+    #[test]
+    fn unterminated_block_comment() {
+        assert_eq!(parse_with("[1, /* 2", &lenient()).unwrap_err().to_string(), "Unterminated comment at position 4");
+    }
+
+    // --- Literal tests ---
+
+    #[test]
+    fn parse_literals() {
+        assert_eq!(parse("null").unwrap(), RdnValue::Null);
+        assert_eq!(parse("true").unwrap(), RdnValue::Bool(true));
+        assert_eq!(parse("false").unwrap(), RdnValue::Bool(false));
+    }
+
+    #[test]
+    fn parse_misspelled_literal_is_err() {
+        assert_eq!(parse("nul").unwrap_err().to_string(), "Expected 'null' at position 0");
+        assert!(parse("tru").is_err());
+        assert!(parse("falsey").is_err());
+    }
+
+    // --- Number tests ---
+
+    #[test]
+    fn parse_integers_and_floats() {
+        assert_eq!(parse("0").unwrap(), n(0.0));
+        assert_eq!(parse("42").unwrap(), n(42.0));
+        assert_eq!(parse("-17").unwrap(), n(-17.0));
+        assert_eq!(parse("2.75").unwrap(), n(2.75));
+        assert_eq!(parse("-0.5").unwrap(), n(-0.5));
+        assert_eq!(parse("1e3").unwrap(), n(1000.0));
+        assert_eq!(parse("2.5E-2").unwrap(), n(0.025));
+        assert_eq!(parse("1e+2").unwrap(), n(100.0));
+    }
+
+    #[test]
+    fn parse_negative_zero_keeps_sign() {
+        let RdnValue::Number(z) = parse("-0").unwrap() else { unreachable!() };
+        assert!(z == 0.0 && z.is_sign_negative());
+    }
+
+    #[test]
+    fn parse_malformed_numbers_are_err() {
+        assert_eq!(parse("01").unwrap_err().to_string(), "Leading zeros not allowed at position 1");
+        assert_eq!(parse("1.").unwrap_err().to_string(), "Expected digit after decimal point at position 2");
+        assert_eq!(parse("1e").unwrap_err().to_string(), "Expected digit in exponent at position 2");
+        assert_eq!(parse("-").unwrap_err().to_string(), "Expected digit at position 1");
+        assert!(parse(".5").is_err());
+        assert!(parse("+1").is_err());
+    }
+
+    // --- Special number tests ---
+
+    #[test]
+    fn parse_special_numbers() {
+        assert!(matches!(parse("NaN").unwrap(), RdnValue::Number(x) if x.is_nan()));
+        assert_eq!(parse("Infinity").unwrap(), n(f64::INFINITY));
+        assert_eq!(parse("-Infinity").unwrap(), n(f64::NEG_INFINITY));
+        assert_eq!(
+            parse("Map{Infinity => [-Infinity], 1 => Set{Infinity}}").unwrap(),
+            RdnValue::Map(vec![
+                (n(f64::INFINITY), RdnValue::Array(vec![n(f64::NEG_INFINITY)])),
+                (n(1.0), RdnValue::Set(vec![n(f64::INFINITY)])),
+            ])
+        );
+    }
+
+    #[test]
+    fn special_numbers_round_trip_through_display() {
+        for input in ["Infinity", "-Infinity", "NaN"] {
+            assert_eq!(parse(input).unwrap().to_string(), input);
+        }
+    }
+
+    #[test]
+    fn parse_misspelled_special_numbers_are_err() {
+        assert_eq!(parse("nan").unwrap_err().to_string(), "Expected 'null' at position 0");
+        assert_eq!(parse("Inf").unwrap_err().to_string(), "Expected 'Infinity' at position 0");
+        assert_eq!(parse("-Inf").unwrap_err().to_string(), "Expected 'Infinity' at position 1");
+        assert_eq!(parse("+Infinity").unwrap_err().to_string(), "Unexpected character '+' at position 0");
+    }
+
+    #[test]
+    fn infinity_aliases_option() {
+        let options = ParseOptions { infinity_aliases: true, ..ParseOptions::default() };
+        for input in ["inf", "Inf", "+inf", "+Inf", "+Infinity", "Infinity"] {
+            assert_eq!(parse_with(input, &options).unwrap(), n(f64::INFINITY), "{input}");
+        }
+        for input in ["-inf", "-Inf", "-Infinity"] {
+            assert_eq!(parse_with(input, &options).unwrap(), n(f64::NEG_INFINITY), "{input}");
+        }
+        let value = parse_with("Map{inf => [-Inf, -1]}", &options).unwrap();
+        assert_eq!(crate::stringify(&value), "Map{Infinity=>[-Infinity,-1]}");
+        assert!(parse_with("INF", &options).is_err());
+        assert!(parse_with("infinite", &options).is_err());
+        assert!(parse_with("+1", &options).is_err());
+    }
+
+    // --- BigInt and Decimal tests ---
+
+    fn big(v: &str) -> RdnValue {
+        RdnValue::BigInt(BigInt::new(v).unwrap())
+    }
+
+    #[test]
+    fn parse_bigint() {
+        assert_eq!(parse("0n").unwrap(), big("0"));
+        assert_eq!(parse("-0n").unwrap(), big("-0"));
+        assert_eq!(parse("42n").unwrap(), big("42"));
+        assert_eq!(parse("-123n").unwrap(), big("-123"));
+        let digits = "123456789".repeat(20);
+        assert_eq!(parse(&format!("{digits}n")).unwrap(), big(&digits));
+    }
+
+    #[test]
+    fn parse_integers_at_safe_integer_boundary() {
+        // 2^53 - 1 is the largest integer every smaller one fits an f64 with.
+        assert_eq!(parse("9007199254740991").unwrap(), n(9_007_199_254_740_991.0));
+        assert_eq!(parse("-9007199254740991").unwrap(), n(-9_007_199_254_740_991.0));
+        // 2^53 + 1 has no f64, so as a Number it rounds to 2^53.
+        assert_eq!(parse("9007199254740993").unwrap(), n(9_007_199_254_740_992.0));
+        assert_eq!(parse("9007199254740993").unwrap(), parse("9007199254740992").unwrap());
+        assert_eq!(crate::stringify(&parse("9007199254740993").unwrap()), "9007199254740992");
+        // With `n` every digit is kept.
+        assert_eq!(parse("9007199254740993n").unwrap(), big("9007199254740993"));
+        assert_eq!(parse("-9007199254740993n").unwrap(), big("-9007199254740993"));
+        assert_eq!(crate::stringify(&parse("9007199254740993n").unwrap()), "9007199254740993n");
+    }
+
+    #[test]
+    fn parse_bigint_in_container() {
+        assert_eq!(parse("[1n, 2]").unwrap(), RdnValue::Array(vec![big("1"), n(2.0)]));
+    }
+
+    #[test]
+    fn parse_bigint_rejects_float_forms() {
+        assert_eq!(parse("1.5n").unwrap_err().to_string(), "BigInt cannot have decimal point or exponent at position 3");
+        assert_eq!(parse("1e10n").unwrap_err().to_string(), "BigInt cannot have decimal point or exponent at position 4");
+        assert_eq!(parse("01n").unwrap_err().to_string(), "Leading zeros not allowed at position 1");
+        assert!(parse("n").is_err());
+        assert!(parse("1nn").is_err());
+    }
+
+    fn decimals() -> ParseOptions {
+        ParseOptions { allow_decimal_literals: true, ..ParseOptions::default() }
+    }
+
+    #[test]
+    fn parse_decimal() {
+        let options = decimals();
+        assert_eq!(parse_with("3.14m", &options).unwrap(), RdnValue::Decimal(Decimal::new("3.14").unwrap()));
+        assert_eq!(parse_with("-0.50m", &options).unwrap(), RdnValue::Decimal(Decimal::new("-0.50").unwrap()));
+        assert_eq!(parse_with("42m", &options).unwrap(), RdnValue::Decimal(Decimal::new("42").unwrap()));
+        assert_eq!(parse_with("1e2m", &options).unwrap_err().to_string(), "Decimal cannot have an exponent at position 3");
+    }
+
+    #[test]
+    fn parse_decimal_is_opt_in() {
+        assert!(parse("3.14m").is_err());
+        assert!(parse("[42m]").is_err());
+        assert_eq!(parse_with("1.5m", &with_units()).unwrap_err().to_string(), "Unknown unit suffix 'm' at position 3");
+    }
+
+    // --- Date tests ---
+
+    fn date(millis: f64) -> RdnValue {
+        RdnValue::Date(RdnDate { millis })
+    }
+
+    #[test]
+    fn parse_datetime_utc() {
+        assert_eq!(parse("@2024-01-15T10:30:00.000Z").unwrap(), date(1_705_314_600_000.0));
+        assert_eq!(parse("@2024-01-15T10:30:00.123Z").unwrap(), date(1_705_314_600_123.0));
+        assert_eq!(parse("@1970-01-01T00:00:00Z").unwrap(), date(0.0));
+        assert_eq!(parse("@2000-01-01T00:00:00Z").unwrap(), date(946_684_800_000.0));
+        assert_eq!(parse("@1969-12-31T23:59:59.999Z").unwrap(), date(-1.0));
+    }
+
+    #[test]
+    fn parse_datetime_offsets() {
+        assert_eq!(parse("@2024-01-15T12:30:00+02:00").unwrap(), date(1_705_314_600_000.0));
+        assert_eq!(parse("@2024-01-15T05:30:00.000-05:00").unwrap(), date(1_705_314_600_000.0));
+    }
+
+    #[test]
+    fn parse_bare_date_is_midnight_utc() {
+        assert_eq!(parse("@1970-01-01").unwrap(), date(0.0));
+        assert_eq!(parse("@2024-01-15").unwrap(), date(1_705_276_800_000.0));
+        assert_eq!(parse("@2024-02-29").unwrap(), date(1_709_164_800_000.0));
+        assert_eq!(parse("[@2024-01-15]").unwrap(), RdnValue::Array(vec![date(1_705_276_800_000.0)]));
+    }
+
+    #[test]
+    fn parse_bare_date_invalid() {
+        assert!(parse("@2023-02-29").unwrap_err().to_string().starts_with("Invalid date: "));
+        assert!(parse("@2024-1-5").unwrap_err().to_string().starts_with("Invalid date: "));
+        assert!(parse("@2024-01-5").is_err());
+        assert!(parse("@2024-01-15T").is_err());
+    }
+
+    #[test]
+    fn parse_unix_timestamps() {
+        assert_eq!(parse("@0").unwrap(), date(0.0));
+        assert_eq!(parse("@1705314600").unwrap(), date(1_705_314_600_000.0));
+        assert_eq!(parse("@9999999999").unwrap(), date(9_999_999_999_000.0));
+        assert_eq!(parse("@1705314600123").unwrap(), date(1_705_314_600_123.0));
+        assert_eq!(parse("[@1, @2]").unwrap(), RdnValue::Array(vec![date(1000.0), date(2000.0)]));
+    }
+
+    #[test]
+    fn parse_expanded_year_dates() {
+        assert_eq!(parse("@+010000-01-01T00:00:00.000Z").unwrap(), date(253_402_300_800_000.0));
+        assert_eq!(parse("[@-000001-12-31]").unwrap(), RdnValue::Array(vec![date(-62_167_305_600_000.0)]));
+        assert_eq!(parse("@+275760-09-13T00:00:00.001Z").unwrap_err().to_string(), "Invalid date: date out of range at position 1");
+        assert_eq!(parse("@-").unwrap_err().to_string(), "Invalid @ literal at position 1");
+        for input in ["@8640000000000000", "@253402300800000", "@-000001-01-01"] {
+            let value = parse(input).unwrap();
+            assert_eq!(parse(&crate::stringify(&value)).unwrap(), value, "{input}");
+        }
+    }
+
+    #[test]
+    fn parse_unix_timestamp_out_of_range() {
+        assert_eq!(parse("@8640000000000000").unwrap(), date(8.64e15));
+        assert_eq!(parse("@8640000000000001").unwrap_err().to_string(), "timestamp out of range at position 1");
+        assert_eq!(parse("[@9999999999999999999]").unwrap_err().to_string(), "timestamp out of range at position 2");
+    }
+
+    #[test]
+    fn parse_unix_timestamp_followed_by_colon() {
+        assert_eq!(parse("@1705314600:").unwrap_err().to_string(), "Unexpected data after value at position 11");
+        assert_eq!(parse("@1:00").unwrap_err().to_string(), "Unexpected data after value at position 2");
+    }
+
+    #[test]
+    fn parse_datetime_in_container() {
+        assert_eq!(
+            parse(r#"{"at": @2024-01-15T10:30:00Z, "n": 1}"#).unwrap(),
+            obj(vec![("at", date(1_705_314_600_000.0)), ("n", n(1.0))])
+        );
+    }
+
+    #[test]
+    fn parse_invalid_dates_are_err() {
+        assert!(parse("@2024-13-40T00:00:00Z").unwrap_err().to_string().starts_with("Invalid date: month must be 01-12"));
+        assert!(parse("@2024-02-30T00:00:00Z").unwrap_err().to_string().ends_with("at position 1"));
+        assert!(parse("@2024-01-15T10:30:00").is_err());
+        assert_eq!(parse("@not-a-date").unwrap_err().to_string(), "Invalid @ literal at position 1");
+        assert_eq!(parse("@").unwrap_err().to_string(), "Invalid @ literal at position 1");
+    }
+
+    // --- TimeOnly tests ---
+
+    fn time(hours: u8, minutes: u8, seconds: u8, milliseconds: u16) -> RdnValue {
+        RdnValue::TimeOnly(RdnTimeOnly::new(hours, minutes, seconds, milliseconds).unwrap())
+    }
+
+    #[test]
+    fn parse_time_only() {
+        assert_eq!(parse("@00:00:00").unwrap(), time(0, 0, 0, 0));
+        assert_eq!(parse("@14:30:00").unwrap(), time(14, 30, 0, 0));
+        assert_eq!(parse("@23:59:59.999").unwrap(), time(23, 59, 59, 999));
+        assert_eq!(parse("@08:05:09.007").unwrap(), time(8, 5, 9, 7));
+        assert_eq!(
+            parse(r#"{"t": @14:30:00, "d": @2024-01-15}"#).unwrap(),
+            obj(vec![("t", time(14, 30, 0, 0)), ("d", date(1_705_276_800_000.0))])
+        );
+    }
+
+    #[test]
+    fn parse_time_only_out_of_range() {
+        assert_eq!(parse("@24:00:00").unwrap_err().to_string(), "Invalid time: hours must be 0-23, got 24 at position 1");
+        assert_eq!(parse("@12:60:00").unwrap_err().to_string(), "Invalid time: minutes must be 0-59, got 60 at position 1");
+        assert_eq!(parse("@12:00:60").unwrap_err().to_string(), "Invalid time: seconds must be 0-59, got 60 at position 1");
+    }
+
+    #[test]
+    fn parse_time_only_malformed() {
+        assert_eq!(parse("@14:30").unwrap_err().to_string(), "Expected ':' at position 6");
+        assert_eq!(parse("@14:3:00").unwrap_err().to_string(), "Expected digit at position 5");
+        assert_eq!(parse("@14:30:00.5").unwrap_err().to_string(), "Expected digit at position 11");
+        assert!(parse("@1:30:00").is_err());
+    }
+
+    // --- Duration tests ---
+
+    fn duration(iso: &str) -> RdnValue {
+        RdnValue::Duration(RdnDuration { iso: iso.to_string() })
+    }
+
+    #[test]
+    fn parse_durations() {
+        assert_eq!(parse("@P1Y2M3DT4H5M6S").unwrap(), duration("P1Y2M3DT4H5M6S"));
+        assert_eq!(parse("@PT1H").unwrap(), duration("PT1H"));
+        assert_eq!(parse("@P1D").unwrap(), duration("P1D"));
+        assert_eq!(parse("@P3W").unwrap(), duration("P3W"));
+        assert_eq!(parse("@PT0.5S").unwrap(), duration("PT0.5S"));
+        assert_eq!(
+            parse("[@P1D, @PT5M]").unwrap(),
+            RdnValue::Array(vec![duration("P1D"), duration("PT5M")])
+        );
+    }
+
+    #[test]
+    fn parse_malformed_durations() {
+        assert_eq!(parse("@P").unwrap_err().to_string(), "Invalid duration: duration has no components: P at position 1");
+        assert!(parse("@PT").unwrap_err().to_string().starts_with("Invalid duration: duration has 'T' but no time components"));
+        assert!(parse("@P1DT").is_err());
+        assert!(parse("@P1H").is_err());
+        assert!(parse("@P0.5D").is_err());
+        assert!(parse("@P1D2Y").is_err());
+        assert_eq!(parse("@P1Dx").unwrap_err().to_string(), "Unexpected data after value at position 4");
+    }
+
+    // --- RegExp tests ---
+
+    fn regexp(source: &str, flags: &str) -> RdnValue {
+        RdnValue::RegExp(RdnRegExp::new(source, flags).unwrap())
+    }
+
+    #[test]
+    fn parse_regexps() {
+        assert_eq!(parse(r"/\d+/gi").unwrap(), regexp(r"\d+", "gi"));
+        assert_eq!(parse(r"/a\/b/").unwrap(), regexp(r"a\/b", ""));
+        assert_eq!(parse("/^[a-z]+$/i").unwrap(), regexp("^[a-z]+$", "i"));
+        assert_eq!(parse(r"/\\/").unwrap(), regexp(r"\\", ""));
+        assert_eq!(parse(r"/[a/b]\//").unwrap(), regexp(r"[a/b]\/", ""));
+        assert_eq!(parse(r"/[\]/]/").unwrap(), regexp(r"[\]/]", ""));
+        assert_eq!(
+            parse(r#"{"re": /x/dgimsuvy}"#).unwrap(),
+            obj(vec![("re", regexp("x", "dgimsuvy"))])
+        );
+    }
+
+    #[test]
+    fn parse_regexp_url_pattern() {
+        let v = parse(r"/^https?:\/\/[\w.-]+\.[a-z]{2,}\/?$/gi").unwrap();
+        assert_eq!(v, regexp(r"^https?:\/\/[\w.-]+\.[a-z]{2,}\/?$", "gi"));
+    }
+
+    #[test]
+    fn parse_regexp_errors() {
+        assert_eq!(parse("/a/gg").unwrap_err().to_string(), "Invalid regular expression: duplicate regex flag: g at position 3");
+        assert_eq!(parse("/a/x").unwrap_err().to_string(), "Invalid regular expression: invalid regex flag: x at position 3");
+        assert_eq!(parse("/abc").unwrap_err().to_string(), "Unterminated regular expression at position 0");
+        assert_eq!(parse(r"/a\/").unwrap_err().to_string(), "Unterminated regular expression at position 0");
+        assert_eq!(parse(r"/a\").unwrap_err().to_string(), "Unterminated regular expression at position 0");
+        assert_eq!(parse("//").unwrap_err().to_string(), "Empty regular expression at position 0");
+        assert_eq!(parse("/[a/").unwrap_err().to_string(), "Unterminated regular expression at position 0");
+    }
+
+    // --- Map tests ---
+
+    #[test]
+    fn parse_explicit_maps() {
+        assert_eq!(parse("Map{}").unwrap(), RdnValue::Map(vec![]));
+        assert_eq!(parse("Map{ }").unwrap(), RdnValue::Map(vec![]));
+        assert_eq!(
+            parse(r#"Map{"a" => 1, "b" => 2}"#).unwrap(),
+            RdnValue::Map(vec![(s("a"), n(1.0)), (s("b"), n(2.0))])
+        );
+        assert_eq!(
+            parse(r#"Map{1 => "a", [1,2] => true, null=>null}"#).unwrap(),
+            RdnValue::Map(vec![
+                (n(1.0), s("a")),
+                (RdnValue::Array(vec![n(1.0), n(2.0)]), RdnValue::Bool(true)),
+                (RdnValue::Null, RdnValue::Null),
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_nested_maps_and_duplicate_keys() {
+        assert_eq!(
+            parse(r#"Map{Map{1 => 2} => Map{"k" => @2024-01-15}, 1 => 1, 1 => 2}"#).unwrap(),
+            RdnValue::Map(vec![
+                (RdnValue::Map(vec![(n(1.0), n(2.0))]), RdnValue::Map(vec![(s("k"), date(1_705_276_800_000.0))])),
+                (n(1.0), n(1.0)),
+                (n(1.0), n(2.0)),
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_explicit_map_errors() {
+        assert_eq!(parse(r#"Map{"a": 1}"#).unwrap_err().to_string(), "Expected '=>' in map entry at position 7");
+        assert_eq!(parse(r#"Map{"a" = 1}"#).unwrap_err().to_string(), "Expected '=>' in map entry at position 8");
+        assert_eq!(parse("Map {}").unwrap_err().to_string(), "Expected 'Map{' at position 0");
+        assert_eq!(parse(r#"Map{1 => 2"#).unwrap_err().to_string(), "Expected '}' at position 10");
+        assert!(parse(r#"Map{1 => 2,}"#).is_err());
+        assert!(parse(r#"Map{1 =>}"#).is_err());
+    }
+
+    // --- Set tests ---
+
+    #[test]
+    fn parse_explicit_sets() {
+        assert_eq!(parse("Set{}").unwrap(), RdnValue::Set(vec![]));
+        assert_eq!(parse("Set{1, 2, 3}").unwrap(), RdnValue::Set(vec![n(1.0), n(2.0), n(3.0)]));
+        assert_eq!(
+            parse(r#"Set{"a", 1, null, [true], @14:30:00}"#).unwrap(),
+            RdnValue::Set(vec![
+                s("a"),
+                n(1.0),
+                RdnValue::Null,
+                RdnValue::Array(vec![RdnValue::Bool(true)]),
+                time(14, 30, 0, 0),
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_nested_sets_keep_duplicates() {
+        assert_eq!(
+            parse("Set{Set{}, Set{1, 1}, Map{1 => Set{2}}}").unwrap(),
+            RdnValue::Set(vec![
+                RdnValue::Set(vec![]),
+                RdnValue::Set(vec![n(1.0), n(1.0)]),
+                RdnValue::Map(vec![(n(1.0), RdnValue::Set(vec![n(2.0)]))]),
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_explicit_set_errors() {
+        assert_eq!(parse("Set{1 2}").unwrap_err().to_string(), "Expected '}' at position 6");
+        assert_eq!(parse("Set[1]").unwrap_err().to_string(), "Expected 'Set{' at position 0");
+        assert!(parse("Set{1,}").is_err());
+        assert!(parse("Set{1").is_err());
+    }
+
+    // --- Brace disambiguation tests ---
+
+    #[test]
+    fn parse_brace_shapes() {
+        assert_eq!(parse("{}").unwrap(), obj(vec![]));
+        assert_eq!(parse(r#"{"a": 1}"#).unwrap(), obj(vec![("a", n(1.0))]));
+        assert_eq!(parse(r#"{"a" => 1}"#).unwrap(), RdnValue::Map(vec![(s("a"), n(1.0))]));
+        assert_eq!(parse("{1 => [2], (3) => {}}").unwrap(), parse("Map{1 => [2], [3] => {}}").unwrap());
+        assert_eq!(parse("{1, 2}").unwrap(), RdnValue::Set(vec![n(1.0), n(2.0)]));
+        assert_eq!(parse(r#"{"a"}"#).unwrap(), RdnValue::Set(vec![s("a")]));
+        assert_eq!(parse(r#"{ "a" , "b" }"#).unwrap(), RdnValue::Set(vec![s("a"), s("b")]));
+    }
+
+    #[test]
+    fn parse_nested_brace_shapes() {
+        assert_eq!(
+            parse(r#"{"m": {{} => {1}}, "s": {{"x": 1}}}"#).unwrap(),
+            obj(vec![
+                ("m", RdnValue::Map(vec![(obj(vec![]), RdnValue::Set(vec![n(1.0)]))])),
+                ("s", RdnValue::Set(vec![obj(vec![("x", n(1.0))])])),
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_brace_errors() {
+        assert_eq!(parse("{1: 2}").unwrap_err().to_string(), "Object key must be a string at position 1");
+        assert_eq!(parse(r#"{"a": 1, 2: 3}"#).unwrap_err().to_string(), "Object key must be a string at position 9");
+        assert_eq!(parse(r#"{"a" => 1, "b": 2}"#).unwrap_err().to_string(), "Expected '=>' in map entry at position 14");
+        assert_eq!(parse(r#"{"a" = 1}"#).unwrap_err().to_string(), "Expected '=>' in map entry at position 5");
+        assert_eq!(parse(r#"{"a", "b": 1}"#).unwrap_err().to_string(), "Expected '}' at position 9");
+        assert_eq!(parse(r#"{"a""#).unwrap_err().to_string(), "Unterminated brace expression at position 4");
+        assert!(parse("{1,}").is_err());
+    }
+
+    // --- Tuple tests ---
+
+    #[test]
+    fn parse_tuples() {
+        assert_eq!(parse("()").unwrap(), RdnValue::Array(vec![]));
+        assert_eq!(parse("( )").unwrap(), RdnValue::Array(vec![]));
+        assert_eq!(parse("(1)").unwrap(), RdnValue::Array(vec![n(1.0)]));
+        assert_eq!(parse("(1, 2, 3)").unwrap(), parse("[1, 2, 3]").unwrap());
+        assert_eq!(
+            parse(r#"("x", 2n, (1, [2]), {"k": ()})"#).unwrap(),
+            RdnValue::Array(vec![
+                s("x"),
+                RdnValue::BigInt(BigInt::new("2").unwrap()),
+                RdnValue::Array(vec![n(1.0), RdnValue::Array(vec![n(2.0)])]),
+                obj(vec![("k", RdnValue::Array(vec![]))]),
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_tuple_errors() {
+        assert_eq!(parse("(1,)").unwrap_err().to_string(), "Unexpected character ')' at position 3");
+        assert_eq!(parse("(1, 2]").unwrap_err().to_string(), "Expected ')' at position 5");
+        assert!(parse("(1").is_err());
+    }
+
+    // --- Unit suffix tests ---
+
+    fn with_units() -> ParseOptions {
+        ParseOptions::default().with_unit_suffixes(ParseOptions::COMMON_UNIT_SUFFIXES)
+    }
+
+    #[test]
+    fn parse_unit_suffixes() {
+        let options = with_units();
+        assert_eq!(parse_with("50%", &options).unwrap(), n(0.5));
+        assert_eq!(parse_with("10ms", &options).unwrap(), n(10.0));
+        assert_eq!(parse_with("1.5s", &options).unwrap(), n(1500.0));
+        assert_eq!(parse_with("-2GB", &options).unwrap(), n(-2e9));
+        assert_eq!(parse_with("1e3KB", &options).unwrap(), n(1e6));
+        assert_eq!(
+            parse_with(r#"{"timeout": 30s, "max": 64MB}"#, &options).unwrap(),
+            obj(vec![("timeout", n(30_000.0)), ("max", n(64e6))])
+        );
+    }
+
+    #[test]
+    fn parse_unit_suffixes_keep_plain_and_suffixed_literals() {
+        let options = with_units();
+        assert_eq!(parse_with("42", &options).unwrap(), n(42.0));
+        assert_eq!(parse_with("42n", &options).unwrap(), RdnValue::BigInt(BigInt::new("42").unwrap()));
+        let options = ParseOptions { allow_decimal_literals: true, ..with_units() };
+        assert_eq!(parse_with("1.5m", &options).unwrap(), RdnValue::Decimal(Decimal::new("1.5").unwrap()));
+        let minutes = ParseOptions::default().with_unit_suffixes(&[("m", 60_000.0)]);
+        assert_eq!(parse_with("2m", &minutes).unwrap(), n(120_000.0));
+    }
+
+    #[test]
+    fn parse_unit_suffix_errors() {
+        let options = with_units();
+        assert_eq!(parse_with("10kb", &options).unwrap_err().to_string(), "Unknown unit suffix 'kb' at position 2");
+        assert_eq!(parse_with("10nm", &options).unwrap_err().to_string(), "Unknown unit suffix 'nm' at position 2");
+        assert!(parse("50%").is_err());
+        assert!(parse("10ms").is_err());
+    }
+
+    // --- Binary tests ---
+
+    #[test]
+    fn parse_base64_binary() {
+        assert_eq!(parse(r#"b"""#).unwrap(), RdnValue::Binary(vec![]));
+        assert_eq!(parse(r#"b"SGVsbG8=""#).unwrap(), RdnValue::Binary(b"Hello".to_vec()));
+        assert_eq!(parse(r#"b"AAEC/w==""#).unwrap(), RdnValue::Binary(vec![0, 1, 2, 255]));
+        assert_eq!(
+            parse(r#"{"data": b"AQID"}"#).unwrap(),
+            obj(vec![("data", RdnValue::Binary(vec![1, 2, 3]))])
+        );
+    }
+
+    #[test]
+    fn parse_base64_binary_errors() {
+        assert_eq!(
+            parse(r#"b"SGVsbG8""#).unwrap_err().to_string(),
+            "Invalid binary literal: invalid base64: length must be a multiple of 4 at position 0"
+        );
+        assert!(parse(r#"b"SGVsbG8=====""#).unwrap_err().to_string().contains("too much padding"));
+        assert!(parse(r#"b"SG=sbG8=""#).unwrap_err().to_string().contains("padding before end of data"));
+        assert!(parse(r#"b"SGVs bG8""#).unwrap_err().to_string().contains("invalid base64 character: ' '"));
+        assert!(parse(r#"b"SGVsbG9=""#).unwrap_err().to_string().contains("non-zero padding bits"));
+        assert_eq!(parse(r#"b"AAAA"#).unwrap_err().to_string(), "Unterminated binary literal at position 0");
+        assert_eq!(parse("b'AAAA'").unwrap_err().to_string(), "Expected '\"' after 'b' at position 1");
+    }
+
+    #[test]
+    fn base32_literals_option() {
+        let options = ParseOptions { allow_base32_literals: true, ..ParseOptions::default() };
+        assert_eq!(parse_with(r#"z"MZXW6YTBOI======""#, &options).unwrap(), RdnValue::Binary(b"foobar".to_vec()));
+        let both = parse_with(r#"[z"", b"Zm9v"]"#, &options).unwrap();
+        assert_eq!(both, RdnValue::Array(vec![RdnValue::Binary(Vec::new()), RdnValue::Binary(b"foo".to_vec())]));
+        assert_eq!(
+            parse_with(r#"z"MZXW6YQ""#, &options).unwrap_err().to_string(),
+            "Invalid binary literal: invalid base32: length must be a multiple of 8 at position 0"
+        );
+        assert!(parse_with(r#"z"mzxw6yq=""#, &options).is_err());
+        assert_eq!(parse(r#"z"MY======""#).unwrap_err().to_string(), "Unexpected character 'z' at position 0");
+    }
+
+    #[test]
+    fn parse_hex_binary() {
+        assert_eq!(parse(r#"x"""#).unwrap(), RdnValue::Binary(vec![]));
+        assert_eq!(parse(r#"x"00ff""#).unwrap(), RdnValue::Binary(vec![0x00, 0xff]));
+        assert_eq!(parse(r#"x"48656C6C6F""#).unwrap(), RdnValue::Binary(b"Hello".to_vec()));
+        let bytes = RdnValue::Binary(vec![0xff, 0x00, 0xab]);
+        assert_eq!(parse(r#"[x"FF00AB", x"ff00ab"]"#).unwrap(), RdnValue::Array(vec![bytes.clone(), bytes]));
+    }
+
+    #[test]
+    fn parse_hex_binary_errors() {
+        assert_eq!(
+            parse(r#"x"abc""#).unwrap_err().to_string(),
+            "Invalid binary literal: invalid hex: odd number of digits at position 0"
+        );
+        assert_eq!(parse(r#"x"0g""#).unwrap_err().to_string(), "Invalid binary literal: invalid hex character: 'g' at position 0");
+        assert!(parse(r#"x"00 ff""#).is_err());
+        assert_eq!(parse(r#"x"00"#).unwrap_err().to_string(), "Unterminated binary literal at position 0");
+        assert_eq!(parse("x00").unwrap_err().to_string(), "Expected '\"' after 'x' at position 1");
+    }
+
+    // --- String tests ---
+
+    #[test]
+    fn parse_plain_string() {
+        assert_eq!(parse(r#""hello""#).unwrap(), s("hello"));
+        assert_eq!(parse(r#""""#).unwrap(), s(""));
+        assert_eq!(parse("\"caf\u{e9} \u{1F600}\"").unwrap(), s("caf\u{e9} \u{1F600}"));
+    }
+
+    #[test]
+    fn parse_string_escapes() {
+        assert_eq!(parse(r#""a\"b\\c\/d""#).unwrap(), s("a\"b\\c/d"));
+        assert_eq!(parse(r#""\b\f\n\r\t""#).unwrap(), s("\u{08}\u{0C}\n\r\t"));
+        assert_eq!(parse(r#""\u0041\u00e9\u0000""#).unwrap(), s("A\u{e9}\u{0}"));
+    }
+
+    #[test]
+    fn parse_null_escape() {
+        let value = parse(r#""a\0b""#).unwrap();
+        assert_eq!(value, s("a\u{0}b"));
+        assert_eq!(parse(r#""\0""#).unwrap(), s("\u{0}"));
+        let text = crate::stringify(&parse(r#""\0""#).unwrap());
+        assert_eq!(text, r#""\u0000""#);
+        assert_eq!(parse(&text).unwrap(), s("\u{0}"));
+        assert_eq!(parse(r#""\01""#).unwrap_err().to_string(), "Invalid escape sequence '\\0' at position 2");
+    }
+
+    #[test]
+    fn strict_json_escapes_reject_null_escape() {
+        let strict = ParseOptions { strict_json_escapes: true, ..ParseOptions::default() };
+        let err = parse_with(r#""\0""#, &strict).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid escape sequence '\\0' at position 2");
+        assert_eq!(parse_with(r#""\u0000""#, &strict).unwrap(), s("\u{0}"));
+    }
+
+    #[test]
+    fn parse_string_surrogate_pair() {
+        assert_eq!(parse(r#""\uD83D\uDE00""#).unwrap(), s("\u{1F600}"));
+    }
+
+    #[test]
+    fn parse_string_errors() {
+        assert_eq!(parse(r#""abc"#).unwrap_err().to_string(), "Unterminated string at position 4");
+        assert_eq!(parse("\"a\nb\"").unwrap_err().to_string(), "Unescaped control character in string at position 2");
+        assert_eq!(parse(r#""\x""#).unwrap_err().to_string(), "Invalid escape sequence '\\x' at position 2");
+        assert_eq!(parse(r#""\u12G4""#).unwrap_err().to_string(), "Invalid unicode escape at position 3");
+        assert!(parse(r#""\uD83D""#).unwrap_err().to_string().starts_with("Unpaired surrogate"));
+        assert!(parse(r#""\uDE00""#).unwrap_err().to_string().starts_with("Unpaired surrogate"));
+        assert!(parse(r#""\uD83DA""#).unwrap_err().to_string().starts_with("Unpaired surrogate"));
+    }
+
+    #[test]
+    fn lone_surrogates_error_by_default() {
+        let err = |input: &str| parse(input).unwrap_err().to_string();
+        assert_eq!(err(r#""a\uD83Db""#), "Unpaired surrogate in unicode escape at position 8");
+        assert_eq!(err(r#""a\uDE00b""#), "Unpaired surrogate in unicode escape at position 8");
+        assert_eq!(err(r#""\uD83D\uD83D""#), "Unpaired surrogate in unicode escape at position 7");
+    }
+
+    #[test]
+    fn lone_surrogates_as_replacement_char() {
+        let lenient = ParseOptions { lone_surrogates: LoneSurrogates::ReplacementChar, ..ParseOptions::default() };
+        let parse = |input: &str| parse_with(input, &lenient).unwrap();
+        assert_eq!(parse(r#""a\uD83Db""#), s("a\u{FFFD}b"));
+        assert_eq!(parse(r#""a\uDE00b""#), s("a\u{FFFD}b"));
+        assert_eq!(parse(r#""\uD83D""#), s("\u{FFFD}"));
+        // A high surrogate before another escape leaves that escape alone.
+        assert_eq!(parse(r#""\uD83D\u0041""#), s("\u{FFFD}A"));
+        assert_eq!(parse(r#""\uD83D\uD83D\uDE00""#), s("\u{FFFD}\u{1F600}"));
+        assert_eq!(parse(r#""\uDE00\uD83D""#), s("\u{FFFD}\u{FFFD}"));
+        assert_eq!(parse(r#""\uD83D\uDE00""#), s("\u{1F600}"));
+        assert!(parse_with(r#""\uD83D\u12G4""#, &lenient).is_err());
+    }
+
+    // --- Single quote tests ---
+
+    fn single_quotes() -> ParseOptions {
+        ParseOptions { allow_single_quotes: true, ..ParseOptions::default() }
+    }
+
+    #[test]
+    fn single_quotes_are_rejected_by_default() {
+        assert_eq!(parse("'a'").unwrap_err().to_string(), "Unexpected character ''' at position 0");
+        assert_eq!(parse(r#""\'""#).unwrap_err().to_string(), "Invalid escape sequence '\\'' at position 2");
+    }
+
+    #[test]
+    fn single_quoted_strings() {
+        let options = single_quotes();
+        assert_eq!(parse_with("'hello'", &options).unwrap(), s("hello"));
+        assert_eq!(parse_with(r#"'it\'s A \n \"q\"'"#, &options).unwrap(), s("it's A \n \"q\""));
+        assert_eq!(parse_with(r#"'say "hi"'"#, &options).unwrap(), s("say \"hi\""));
+        assert_eq!(parse_with(r#""it's \'ok\'""#, &options).unwrap(), s("it's 'ok'"));
+        assert_eq!(parse_with("'abc", &options).unwrap_err().to_string(), "Unterminated string at position 4");
+    }
+
+    #[test]
+    fn mixed_quoting_gives_identical_values() {
+        let options = single_quotes();
+        let single = parse_with(r#"{'a': ['x', "y"], "b": Map{'k' => 'v'}, 'c': {'s'}}"#, &options).unwrap();
+        let double = parse(r#"{"a": ["x", "y"], "b": Map{"k" => "v"}, "c": {"s"}}"#).unwrap();
+        assert_eq!(single, double);
+    }
+
+    // --- Unquoted key tests ---
+
+    #[test]
+    fn unquoted_keys() {
+        let options = ParseOptions { allow_unquoted_keys: true, ..ParseOptions::default() };
+        let expected = obj(vec![("name", s("RDN")), ("version", n(1.0)), ("_$x9", n(2.0)), ("quoted key", n(3.0))]);
+        let input = r#"{name: "RDN", version : 1, _$x9:2, "quoted key": 3}"#;
+        assert_eq!(parse_with(input, &options).unwrap(), expected);
+        let literals = obj(vec![("true", RdnValue::Null), ("null", RdnValue::Bool(true))]);
+        assert_eq!(parse_with("{true: null, null: true}", &options).unwrap(), literals);
+        assert_eq!(parse_with("{true, null}", &options).unwrap(), RdnValue::Set(vec![RdnValue::Bool(true), RdnValue::Null]));
+    }
+
+    #[test]
+    fn unquoted_key_errors() {
+        let options = ParseOptions { allow_unquoted_keys: true, ..ParseOptions::default() };
+        assert_eq!(parse("{key: 1}").unwrap_err().to_string(), "Unexpected character 'k' at position 1");
+        let err = parse_with("{1a: 1}", &options).unwrap_err();
+        assert_eq!((err.message(), err.offset()), ("Expected ':', '=>', ',' or '}' after value in brace expression", 2));
+        assert_eq!(parse_with("{a: 1, 2b: 2}", &options).unwrap_err().to_string(), "Object key must be a string at position 7");
+        assert_eq!(parse_with("{a: 1, b-c: 2}", &options).unwrap_err().to_string(), "Object key must be a string at position 7");
+    }
+
+    // --- Radix literal tests ---
+
+    fn radix() -> ParseOptions {
+        ParseOptions { allow_radix_literals: true, ..ParseOptions::default() }
+    }
+
+    #[test]
+    fn radix_literals() {
+        let options = radix();
+        assert_eq!(parse_with("0xFF", &options).unwrap(), n(255.0));
+        assert_eq!(parse_with("0Xff", &options).unwrap(), n(255.0));
+        assert_eq!(parse_with("0o17", &options).unwrap(), n(15.0));
+        assert_eq!(parse_with("0b1010", &options).unwrap(), n(10.0));
+        assert_eq!(parse_with("-0xFF", &options).unwrap(), n(-255.0));
+        assert!(matches!(parse_with("-0x0", &options).unwrap(), RdnValue::Number(v) if v == 0.0 && v.is_sign_negative()));
+        assert_eq!(parse_with("[0, 0.5, 07]", &options).unwrap_err().to_string(), "Leading zeros not allowed at position 10");
+    }
+
+    #[test]
+    fn radix_bigint_literals() {
+        let options = radix();
+        let big = |v: &str| RdnValue::BigInt(BigInt::new(v).unwrap());
+        assert_eq!(parse_with("0xFFn", &options).unwrap(), big("255"));
+        assert_eq!(parse_with("-0b11n", &options).unwrap(), big("-3"));
+        assert_eq!(parse_with("-0x0n", &options).unwrap(), big("0"));
+        assert_eq!(parse_with("0xFFFFFFFFFFFFFFFFFFFFn", &options).unwrap(), big("1208925819614629174706175"));
+        assert_eq!(parse_with("0o7777777777777777777777n", &options).unwrap(), big("73786976294838206463"));
+        assert_eq!(parse_with(&format!("0b1{}n", "0".repeat(100)), &options).unwrap(), big("1267650600228229401496703205376"));
+        assert_eq!(parse_with("0x3B9ACA00n", &options).unwrap(), big("1000000000"));
+    }
+
+    #[test]
+    fn radix_literals_round_to_nearest() {
+        let options = radix();
+        assert_eq!(parse_with("0x1FFFFFFFFFFFFF", &options).unwrap(), n(9_007_199_254_740_991.0));
+        // 2^53 + 1 is a tie, broken to even; 2^53 + 3 breaks upward.
+        assert_eq!(parse_with("0x20000000000001", &options).unwrap(), n(9_007_199_254_740_992.0));
+        assert_eq!(parse_with("0x20000000000003", &options).unwrap(), n(9_007_199_254_740_996.0));
+        // A set bit past the first 64 breaks a tie upward.
+        assert_eq!(parse_with("0x200000000000010000000000000001", &options).unwrap(), n(2f64.powi(117) + 2f64.powi(65)));
+        assert_eq!(parse_with(&format!("0x1{}", "0".repeat(255)), &options).unwrap(), n(2f64.powi(1020)));
+        assert_eq!(parse_with(&format!("-0x1{}", "0".repeat(256)), &options).unwrap(), n(f64::NEG_INFINITY));
+    }
+
+    #[test]
+    fn radix_literals_are_fast_and_bigints_capped() {
+        let options = radix();
+        let long = format!("0x{}", "F".repeat(1_000_000));
+        assert_eq!(parse_with(&long, &options).unwrap(), n(f64::INFINITY));
+        let max = format!("0x{}n", "F".repeat(ParseOptions::MAX_RADIX_BIGINT_DIGITS));
+        assert!(matches!(parse_with(&max, &options).unwrap(), RdnValue::BigInt(_)));
+        let over = format!("[0x{}n]", "F".repeat(ParseOptions::MAX_RADIX_BIGINT_DIGITS + 1));
+        assert_eq!(parse_with(&over, &options).unwrap_err().to_string(), "BigInt literal has more than 10000 digits at position 1");
+    }
+
+    #[test]
+    fn radix_literal_errors() {
+        let options = radix();
+        assert_eq!(parse("0xFF").unwrap_err().to_string(), "Unexpected data after value at position 1");
+        assert_eq!(parse_with("0x", &options).unwrap_err().to_string(), "Expected digit after '0x' at position 2");
+        assert_eq!(parse_with("0o8", &options).unwrap_err().to_string(), "Expected digit after '0o' at position 2");
+        assert_eq!(parse_with("0b102", &options).unwrap_err().to_string(), "Unexpected data after value at position 4");
+    }
+
+    // --- Numeric separator tests ---
+
+    fn separators() -> ParseOptions {
+        ParseOptions { allow_numeric_separators: true, ..ParseOptions::default() }
+    }
+
+    #[test]
+    fn numeric_separators() {
+        let options = separators();
+        assert_eq!(parse_with("1_000_000", &options).unwrap(), n(1_000_000.0));
+        assert_eq!(parse_with("-1_0.2_5e1_0", &options).unwrap(), n(-10.25e10));
+        assert_eq!(parse_with("42_000n", &options).unwrap(), RdnValue::BigInt(BigInt::new("42000").unwrap()));
+        let options = ParseOptions { allow_decimal_literals: true, ..separators() };
+        assert_eq!(parse_with("1_234.5m", &options).unwrap(), RdnValue::Decimal(Decimal::new("1234.5").unwrap()));
+        let options = ParseOptions { allow_radix_literals: true, ..separators() };
+        assert_eq!(parse_with("0xFF_FF", &options).unwrap(), n(65535.0));
+    }
+
+    #[test]
+    fn misplaced_numeric_separators() {
+        let options = separators();
+        let err = |input: &str| parse_with(input, &options).unwrap_err().to_string();
+        assert_eq!(parse("1_000").unwrap_err().to_string(), "Unexpected data after value at position 1");
+        assert_eq!(err("_1"), "Unexpected character '_' at position 0");
+        assert_eq!(err("1_"), "Numeric separator must be between digits at position 1");
+        assert_eq!(err("1__2"), "Numeric separator must be between digits at position 1");
+        assert_eq!(err("1_.5"), "Numeric separator must be between digits at position 1");
+        assert_eq!(err("1._5"), "Numeric separator must be between digits at position 2");
+        assert_eq!(err("1_n"), "Numeric separator must be between digits at position 1");
+    }
+
+    // --- Nesting depth tests ---
+
+    #[test]
+    fn max_depth_allows_nesting_up_to_limit() {
+        let options = ParseOptions { max_depth: 3, ..ParseOptions::default() };
+        assert!(parse_with(r#"[{"a": Map{1 => 2}}]"#, &options).is_ok());
+        assert!(parse_with("(Set{[1]})", &options).is_ok());
+        assert_eq!(parse_with("[[[[]]]]", &options).unwrap_err().to_string(), "Maximum nesting depth of 3 exceeded at position 3");
+        assert_eq!(parse_with("{{{{}}}}", &options).unwrap_err().to_string(), "Maximum nesting depth of 3 exceeded at position 3");
+        assert!(parse_with("((Set{Map{}}))", &options).is_err());
+    }
+
+    #[test]
+    fn deep_nesting_is_an_error_not_a_crash() {
+        let input = format!("{}{}", "[".repeat(100_000), "]".repeat(100_000));
+        let err = parse(&input).unwrap_err();
+        assert_eq!((err.message(), err.offset()), ("Maximum nesting depth of 128 exceeded", 128));
+        let input = format!("{}{}", "[".repeat(128), "]".repeat(128));
+        assert!(parse(&input).is_ok());
+    }
+
+    // --- String length tests ---
+
+    #[test]
+    fn max_string_len_limits_values_and_keys() {
+        let options = ParseOptions { max_string_len: 3, ..ParseOptions::default() };
+        assert!(parse_with(r#"{"abc": ["xyz", "", "\u00e9!"]}"#, &options).is_ok());
+        let err = |input: &str| parse_with(input, &options).unwrap_err().to_string();
+        assert_eq!(err(r#"["ok", "long"]"#), "String exceeds maximum length of 3 bytes at position 7");
+        assert_eq!(err(r#"{"keys": 1}"#), "String exceeds maximum length of 3 bytes at position 1");
+        assert_eq!(err(r#"Map{1 => "four"}"#), "String exceeds maximum length of 3 bytes at position 9");
+        let unquoted = ParseOptions { allow_unquoted_keys: true, ..options.clone() };
+        assert!(parse_with("{abc: 1}", &unquoted).is_ok());
+        assert!(parse_with("{abcd: 1}", &unquoted).is_err());
+    }
+
+    #[test]
+    fn max_string_len_counts_decoded_bytes() {
+        let options = ParseOptions { max_string_len: 2, ..ParseOptions::default() };
+        // Six bytes of input, but one decoded character of two bytes.
+        assert_eq!(parse_with(r#""\u00e9""#, &options).unwrap(), RdnValue::String("\u{e9}".to_string()));
+        assert!(parse_with(r#""\n\n\n""#, &options).is_err());
+        assert!(parse_with(r#""\u00e9a""#, &options).is_err());
+    }
+
+    #[test]
+    fn max_string_len_fails_before_reaching_the_end() {
+        let options = ParseOptions { max_string_len: 8, ..ParseOptions::default() };
+        // The string is never terminated, so only an early check reports the length.
+        let input = format!("\"{}", "a".repeat(100));
+        assert_eq!(parse_with(&input, &options).unwrap_err().message(), "String exceeds maximum length of 8 bytes");
+        let input = format!("\"{}", "\\t".repeat(100));
+        assert_eq!(parse_with(&input, &options).unwrap_err().message(), "String exceeds maximum length of 8 bytes");
+    }
+
+    // --- Span tests ---
+
+    #[test]
+    fn spans_match_source_text() {
+        let input = "{\"outer\": {\"name\":  \"RDN\" , \"tags\": Set{ 1, 2n }}, \"m\": Map{@2024-01-15 => /x/g}}";
+        let (value, spans) = parse_spanned(input).unwrap();
+        assert_eq!(value, parse(input).unwrap());
+        let text = |tree: &SpanTree| &input[tree.span()];
+        assert_eq!(text(&spans), input);
+        let (key, outer) = spans.entry(0).unwrap();
+        assert_eq!(text(key), "\"outer\"");
+        let (key, name) = outer.entry(0).unwrap();
+        assert_eq!((text(key), text(name)), ("\"name\"", "\"RDN\""));
+        let (_, tags) = outer.entry(1).unwrap();
+        assert_eq!(text(tags), "Set{ 1, 2n }");
+        assert_eq!(tags.children().iter().map(text).collect::<Vec<_>>(), ["1", "2n"]);
+        let (_, map) = spans.entry(1).unwrap();
+        let (key, value) = map.entry(0).unwrap();
+        assert_eq!((text(key), text(value)), ("@2024-01-15", "/x/g"));
+        assert!(value.children().is_empty());
+        assert!(map.entry(1).is_none());
+    }
+
+    #[test]
+    fn spans_of_arrays_sets_and_tuples() {
+        let input = " [(1, \"a\"), {true, null}, []] ";
+        let (_, spans) = parse_spanned(input).unwrap();
+        assert_eq!(spans.span(), 1..input.len() - 1);
+        let texts: Vec<_> = spans.children().iter().map(|tree| &input[tree.span()]).collect();
+        assert_eq!(texts, ["(1, \"a\")", "{true, null}", "[]"]);
+        assert_eq!(&input[spans.children()[0].children()[1].span()], "\"a\"");
+        assert_eq!(spans.children()[1].children().len(), 2);
+        assert!(parse_spanned("[1,]").is_err());
+    }
+
+    // --- Container tests ---
+
+    #[test]
+    fn parse_empty_containers() {
+        assert_eq!(parse("[]").unwrap(), RdnValue::Array(vec![]));
+        assert_eq!(parse("{}").unwrap(), obj(vec![]));
+        assert_eq!(parse("[ \n ]").unwrap(), RdnValue::Array(vec![]));
+        assert_eq!(parse("{\t}").unwrap(), obj(vec![]));
+    }
+
+    #[test]
+    fn parse_nested_containers() {
+        let input = r#" { "a" : [1, {"b": null}, []], "c": {"d": [true, "x"]} } "#;
+        assert_eq!(
+            parse(input).unwrap(),
+            obj(vec![
+                ("a", RdnValue::Array(vec![n(1.0), obj(vec![("b", RdnValue::Null)]), RdnValue::Array(vec![])])),
+                ("c", obj(vec![("d", RdnValue::Array(vec![RdnValue::Bool(true), s("x")]))])),
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_object_keeps_order_and_duplicates() {
+        assert_eq!(
+            parse(r#"{"z": 1, "a": 2, "z": 3}"#).unwrap(),
+            obj(vec![("z", n(1.0)), ("a", n(2.0)), ("z", n(3.0))])
+        );
+    }
+
+    #[test]
+    fn parse_malformed_containers_are_err() {
+        assert_eq!(parse(r#"{"a":}"#).unwrap_err().to_string(), "Unexpected character '}' at position 5");
+        assert_eq!(parse("[1,,2]").unwrap_err().to_string(), "Unexpected character ',' at position 3");
+        assert_eq!(parse("[1 2]").unwrap_err().to_string(), "Expected ']' at position 3");
+        assert_eq!(
+            parse(r#"{"a" 1}"#).unwrap_err().to_string(),
+            "Expected ':', '=>', ',' or '}' after value in brace expression at position 5"
+        );
+        assert_eq!(parse(r#"{"a": 1,}"#).unwrap_err().to_string(), "Object key must be a string at position 8");
+        assert_eq!(parse("[1,]").unwrap_err().to_string(), "Unexpected character ']' at position 3");
+        assert_eq!(parse("[1").unwrap_err().to_string(), "Expected ']' at position 2");
+    }
+
+    // --- Partial parse tests ---
+
+    /// Parses `input` with [`parse_partial`], returning the value and the
+    /// truncation offset.
+    fn partial(input: &str) -> (RdnValue, Option<usize>) {
+        let (value, truncation) = parse_partial(input).unwrap_or_else(|e| panic!("{input:?}: {e}"));
+        (value, truncation.map(|t| t.offset()))
+    }
+
+    #[test]
+    fn parse_partial_complete_input() {
+        let input = r#"{"a": [1, "x"], "b": Map{1 => Set{2}}} "#;
+        assert_eq!(partial(input), (parse(input).unwrap(), None));
+        assert_eq!(partial("12"), (n(12.0), None));
+        assert!(parse_partial("[] x").is_err());
+    }
+
+    #[test]
+    fn parse_partial_cut_inside_string() {
+        assert_eq!(partial(r#"["ab", "cd"#), (RdnValue::Array(vec![s("ab")]), Some(7)));
+        assert_eq!(partial(r#"["ab", "c\"d\"#), (RdnValue::Array(vec![s("ab")]), Some(7)));
+        assert_eq!(partial(r#"["ab""#), (RdnValue::Array(vec![s("ab")]), Some(5)));
+        assert_eq!(partial(r#""abc"#), (RdnValue::Null, Some(0)));
+        assert_eq!(partial(r#"[b"AQ"#), (RdnValue::Array(vec![]), Some(1)));
+        assert_eq!(partial("[/a[/]"), (RdnValue::Array(vec![]), Some(1)));
+    }
+
+    #[test]
+    fn parse_partial_cut_inside_number() {
+        // The last number may have had more digits, so it is dropped.
+        assert_eq!(partial("[1, 23"), (RdnValue::Array(vec![n(1.0)]), Some(4)));
+        assert_eq!(partial("[1, 2."), (RdnValue::Array(vec![n(1.0)]), Some(4)));
+        assert_eq!(partial("[1, 2e"), (RdnValue::Array(vec![n(1.0)]), Some(4)));
+        assert_eq!(partial("[1, -"), (RdnValue::Array(vec![n(1.0)]), Some(4)));
+        assert_eq!(partial("[1, 42"), partial("[1, 42n"));
+        assert_eq!(partial("[1, @2024-01-15T10:3"), (RdnValue::Array(vec![n(1.0)]), Some(4)));
+        assert_eq!(partial("[1, tr"), (RdnValue::Array(vec![n(1.0)]), Some(4)));
+        // Anything after it shows that it is complete.
+        assert_eq!(partial("[1, 23 "), (RdnValue::Array(vec![n(1.0), n(23.0)]), Some(7)));
+        assert_eq!(partial("[1, 23,"), (RdnValue::Array(vec![n(1.0), n(23.0)]), Some(7)));
+    }
+
+    #[test]
+    fn parse_partial_cut_inside_object_key() {
+        let one = obj(vec![("a", n(1.0))]);
+        assert_eq!(partial(r#"{"a": 1, "b"#), (one.clone(), Some(9)));
+        assert_eq!(partial(r#"{"a": 1, "b""#), (one.clone(), Some(9)));
+        assert_eq!(partial(r#"{"a": 1, "b":"#), (one.clone(), Some(9)));
+        assert_eq!(partial(r#"{"a": 1, "b": "#), (one.clone(), Some(9)));
+        assert_eq!(partial(r#"{"a": 1, "b": 2"#), (one.clone(), Some(9)));
+        assert_eq!(partial(r#"{"a": 1, "#), (one, Some(9)));
+        // Before its separator, a first key could still start a Set.
+        assert_eq!(partial(r#"{"a""#), (obj(vec![]), Some(1)));
+        assert_eq!(partial("{"), (obj(vec![]), Some(1)));
+    }
+
+    #[test]
+    fn parse_partial_cut_inside_nested_containers() {
+        assert_eq!(
+            partial(r#"{"a": [1, {"b": Set{2, [3, "x"#),
+            (parse(r#"{"a": [1, {"b": Set{2, [3]}}]}"#).unwrap(), Some(27))
+        );
+        assert_eq!(partial("[[1, 2], [3"), (parse("[[1, 2], []]").unwrap(), Some(10)));
+        assert_eq!(partial("Map{1 => [2, 3], 4 =>"), (parse("Map{1 => [2, 3]}").unwrap(), Some(17)));
+        assert_eq!(partial("Map{1 => [2, 3], [4"), (parse("Map{1 => [2, 3]}").unwrap(), Some(17)));
+        assert_eq!(partial("Map{1 => [2, 3"), (parse("Map{1 => [2]}").unwrap(), Some(13)));
+        assert_eq!(partial("{1 => 2, 3 ="), (parse("{1 => 2}").unwrap(), Some(9)));
+        assert_eq!(partial("{1, 2, (3"), (parse("{1, 2, ()}").unwrap(), Some(8)));
+        assert_eq!(partial("[Se"), (RdnValue::Array(vec![]), Some(1)));
+    }
+
+    #[test]
+    fn parse_partial_reports_real_errors() {
+        assert_eq!(parse_partial("[1, tru, 2").unwrap_err().to_string(), "Expected 'true' at position 4");
+        assert_eq!(parse_partial("[1 2").unwrap_err().to_string(), "Expected ']' at position 3");
+        assert_eq!(parse_partial(r#"{"a" 1"#).unwrap_err().to_string(), parse(r#"{"a" 1}"#).unwrap_err().to_string());
+        assert!(parse_partial("[%").is_err());
+    }
+
+    #[test]
+    fn parse_partial_every_prefix() {
+        let input = r#"{"a": [1, -2.5e3, "x\"y", true], "b": Map{@2024-01-15 => /a[/]b/g}, "c": Set{b"AQID", 42n}}"#;
+        for end in 0..=input.len() {
+            let (value, truncation) = partial(&input[..end]);
+            assert_eq!(truncation.is_none(), end == input.len(), "{end}");
+            assert!(truncation.is_none_or(|offset| offset <= end), "{end}");
+            // What was recovered is complete in its own right.
+            if let Some(offset) = truncation.filter(|_| value != RdnValue::Null) {
+                assert!(offset > 0, "{end}");
+                assert_eq!(parse(&crate::stringify(&value)).unwrap(), value, "{end}");
+            }
+        }
+    }
+
+    // --- Document-level tests ---
+
+    #[test]
+    fn parse_skips_surrounding_whitespace() {
+        assert_eq!(parse(" \t\r\n42\n ").unwrap(), n(42.0));
+    }
+
+    #[test]
+    fn parse_every_value_kind_at_top_level() {
+        let cases = [
+            ("null", RdnValue::Null),
+            ("true", RdnValue::Bool(true)),
+            ("-1.5", n(-1.5)),
+            ("NaN", n(f64::NAN)),
+            (r#""x""#, s("x")),
+            ("42n", big("42")),
+            ("@2024-01-15T10:30:00.000Z", date(1_705_314_600_000.0)),
+            ("@14:30:00", RdnValue::TimeOnly(RdnTimeOnly::new(14, 30, 0, 0).unwrap())),
+            ("@P1D", RdnValue::Duration(RdnDuration::new("P1D").unwrap())),
+            ("/a+b/gi", regexp("a+b", "gi")),
+            (r#"b"AQID""#, RdnValue::Binary(vec![1, 2, 3])),
+            (r#"x"ff00""#, RdnValue::Binary(vec![255, 0])),
+            ("(1, 2)", RdnValue::Array(vec![n(1.0), n(2.0)])),
+            ("[1]", RdnValue::Array(vec![n(1.0)])),
+            (r#"{"a": 1}"#, obj(vec![("a", n(1.0))])),
+            ("Map{1 => 2}", RdnValue::Map(vec![(n(1.0), n(2.0))])),
+            ("{1 => 2}", RdnValue::Map(vec![(n(1.0), n(2.0))])),
+            ("Set{1}", RdnValue::Set(vec![n(1.0)])),
+            (r#"{"a", "b"}"#, RdnValue::Set(vec![s("a"), s("b")])),
+        ];
+        for (input, expected) in cases {
+            for text in [input.to_string(), format!(" \n{input}\t ")] {
+                let value = parse(&text).unwrap_or_else(|e| panic!("{text:?}: {e}"));
+                assert_eq!(value.type_name(), expected.type_name(), "{text:?}");
+                if !matches!(expected, RdnValue::Number(e) if e.is_nan()) {
+                    assert_eq!(value, expected, "{text:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn parse_rejects_trailing_data() {
+        assert_eq!(parse("1 2").unwrap_err().to_string(), "Unexpected data after value at position 2");
+        assert!(parse("{} x").is_err());
+    }
+
+    #[test]
+    fn parse_empty_input_is_err() {
+        assert_eq!(parse("").unwrap_err().to_string(), "Unexpected end of input at position 0");
+        assert_eq!(parse("   ").unwrap_err().to_string(), "Unexpected end of input at position 3");
+    }
+
+    #[test]
+    fn parse_unexpected_character_reports_full_char() {
+        assert_eq!(parse("\u{e9}").unwrap_err().to_string(), "Unexpected character '\u{e9}' at position 0");
+    }
 }
@@ -0,0 +1,389 @@
+use crate::types::*;
+
+/// One step of a parsed query.
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    /// Apply the selector to the children of each current node.
+    Child(Selector),
+    /// Apply the selector to each current node and all of its descendants.
+    Descendant(Selector),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Selector {
+    Key(String),
+    Index(usize),
+    Wildcard,
+}
+
+/// Parses a query such as `$.servers[0].host` or `$..id` into segments.
+fn parse_query(query: &str) -> Result<Vec<Segment>, String> {
+    let rest = query.strip_prefix('$').ok_or_else(|| format!("query must start with '$': {query}"))?;
+    let bytes = rest.as_bytes();
+    let mut segments = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'.' if bytes.get(i + 1) == Some(&b'.') => {
+                i += 2;
+                if bytes.get(i) == Some(&b'[') {
+                    let (selector, next) = parse_bracket(rest, i)?;
+                    segments.push(Segment::Descendant(selector));
+                    i = next;
+                } else {
+                    let (selector, next) = parse_dotted(rest, i)?;
+                    segments.push(Segment::Descendant(selector));
+                    i = next;
+                }
+            }
+            b'.' => {
+                let (selector, next) = parse_dotted(rest, i + 1)?;
+                segments.push(Segment::Child(selector));
+                i = next;
+            }
+            b'[' => {
+                let (selector, next) = parse_bracket(rest, i)?;
+                segments.push(Segment::Child(selector));
+                i = next;
+            }
+            other => return Err(format!("unexpected '{}' at offset {} in query", other as char, i + 1)),
+        }
+    }
+    Ok(segments)
+}
+
+/// Parses a dotted name starting at `start` (just after the `.`).
+fn parse_dotted(rest: &str, start: usize) -> Result<(Selector, usize), String> {
+    let end = rest[start..].find(['.', '[']).map_or(rest.len(), |n| start + n);
+    match &rest[start..end] {
+        "" => Err(format!("empty key at offset {} in query", start + 1)),
+        "*" => Ok((Selector::Wildcard, end)),
+        name => Ok((Selector::Key(name.to_string()), end)),
+    }
+}
+
+/// Parses a bracketed selector starting at `start` (on the `[`).
+fn parse_bracket(rest: &str, start: usize) -> Result<(Selector, usize), String> {
+    let inner_start = start + 1;
+    if rest[inner_start..].starts_with('"') {
+        let mut key = String::new();
+        let mut chars = rest[inner_start + 1..].char_indices();
+        while let Some((offset, ch)) = chars.next() {
+            match ch {
+                '"' => {
+                    let close = inner_start + 1 + offset + 1;
+                    if rest.as_bytes().get(close) != Some(&b']') {
+                        return Err(format!("expected ']' at offset {} in query", close + 1));
+                    }
+                    return Ok((Selector::Key(key), close + 1));
+                }
+                '\\' => match chars.next() {
+                    Some((_, escaped @ ('"' | '\\'))) => key.push(escaped),
+                    _ => return Err("invalid escape in quoted query key".to_string()),
+                },
+                c => key.push(c),
+            }
+        }
+        return Err("unterminated quoted key in query".to_string());
+    }
+    let close = rest[inner_start..]
+        .find(']')
+        .map(|n| inner_start + n)
+        .ok_or_else(|| format!("unterminated '[' at offset {} in query", start + 1))?;
+    let inner = &rest[inner_start..close];
+    let selector = if inner == "*" {
+        Selector::Wildcard
+    } else if !inner.is_empty() && inner.bytes().all(|b| b.is_ascii_digit()) {
+        Selector::Index(inner.parse().map_err(|_| format!("index out of range in query: {inner}"))?)
+    } else {
+        return Err(format!("invalid bracket selector in query: [{inner}]"));
+    };
+    Ok((selector, close + 1))
+}
+
+/// Returns the direct children of a container in document order.
+///
+/// A child's position in this list is the step recorded in located paths.
+pub(crate) fn children(value: &RdnValue) -> Vec<&RdnValue> {
+    match value {
+        RdnValue::Array(items) | RdnValue::Set(items) => items.iter().collect(),
+        RdnValue::Object(entries) => entries.iter().map(|(_, v)| v).collect(),
+        RdnValue::Map(entries) => entries.iter().map(|(_, v)| v).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// A matched node and the child positions leading to it from the root.
+type Located<'a> = (&'a RdnValue, Vec<usize>);
+
+fn child_path(path: &[usize], position: usize) -> Vec<usize> {
+    let mut child = path.to_vec();
+    child.push(position);
+    child
+}
+
+/// Applies a selector to the children of a node, pushing matches onto `out`.
+fn select_children<'a>(node: &Located<'a>, selector: &Selector, out: &mut Vec<Located<'a>>) {
+    let (value, path) = node;
+    match selector {
+        Selector::Wildcard => {
+            for (position, child) in children(value).into_iter().enumerate() {
+                out.push((child, child_path(path, position)));
+            }
+        }
+        Selector::Index(index) => {
+            if let RdnValue::Array(items) = value {
+                if let Some(item) = items.get(*index) {
+                    out.push((item, child_path(path, *index)));
+                }
+            }
+        }
+        Selector::Key(key) => {
+            let found = match value {
+                RdnValue::Object(entries) => {
+                    entries.iter().enumerate().find(|(_, (k, _))| k == key).map(|(i, (_, v))| (i, v))
+                }
+                RdnValue::Map(entries) => entries
+                    .iter()
+                    .enumerate()
+                    .find(|(_, (k, _))| matches!(k, RdnValue::String(s) if s == key))
+                    .map(|(i, (_, v))| (i, v)),
+                _ => None,
+            };
+            if let Some((position, child)) = found {
+                out.push((child, child_path(path, position)));
+            }
+        }
+    }
+}
+
+/// Applies a selector to a node and every descendant, in pre-order.
+fn select_descendants<'a>(node: &Located<'a>, selector: &Selector, out: &mut Vec<Located<'a>>) {
+    select_children(node, selector, out);
+    for (position, child) in children(node.0).into_iter().enumerate() {
+        select_descendants(&(child, child_path(&node.1, position)), selector, out);
+    }
+}
+
+/// Runs a parsed query against `root`.
+fn run_query<'a>(root: &'a RdnValue, segments: &[Segment]) -> Vec<Located<'a>> {
+    let mut current = vec![(root, Vec::new())];
+    for segment in segments {
+        let mut next = Vec::new();
+        for node in &current {
+            match segment {
+                Segment::Child(selector) => select_children(node, selector, &mut next),
+                Segment::Descendant(selector) => select_descendants(node, selector, &mut next),
+            }
+        }
+        current = next;
+    }
+    current
+}
+
+impl RdnValue {
+    /// Returns every value matching a JSONPath-style `query`, in document order.
+    ///
+    /// Supported syntax:
+    ///
+    /// - `$` — the root value; every query must start with it
+    /// - `.key` — the member `key` of an Object (or string key of a Map)
+    /// - `["key"]` — same as `.key`, for keys containing `.`, `[`, or spaces
+    ///   (`\"` and `\\` are the only escapes)
+    /// - `[n]` — the `n`th element of an Array (zero-based)
+    /// - `.*` / `[*]` — every child: Array elements, Object and Map values, Set members
+    /// - `..key` / `..*` / `..[n]` — recursive descent: applies the selector at
+    ///   every depth below the current node
+    ///
+    /// Filters, slices, negative indices, and unions are not supported. When an
+    /// Object has duplicate keys, only the first entry matches. A malformed
+    /// query matches nothing.
+    pub fn select(&self, query: &str) -> Vec<&RdnValue> {
+        match parse_query(query) {
+            Ok(segments) => run_query(self, &segments).into_iter().map(|(v, _)| v).collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    /// Returns the single value at a relative `path` such as `tls.cert` or
+    /// `servers[0].host`, or `None` if nothing is there.
+    ///
+    /// The path uses the query syntax of [`RdnValue::select`] without the
+    /// leading `$`, restricted to single-value steps: `.key`, `["key"]`, and
+    /// `[n]`. An empty path is the value itself. Wildcards, recursive descent,
+    /// and malformed paths return `None`.
+    pub fn get_path(&self, path: &str) -> Option<&RdnValue> {
+        let query = if path.is_empty() || path.starts_with('[') { format!("${path}") } else { format!("$.{path}") };
+        let segments = parse_query(&query).ok()?;
+        let single = segments
+            .iter()
+            .all(|segment| matches!(segment, Segment::Child(Selector::Key(_) | Selector::Index(_))));
+        if !single {
+            return None;
+        }
+        run_query(self, &segments).into_iter().next().map(|(v, _)| v)
+    }
+
+    /// Returns `true` if [`RdnValue::get_path`] finds a value at `path`.
+    pub fn has_path(&self, path: &str) -> bool {
+        self.get_path(path).is_some()
+    }
+
+    /// Returns the child-position paths of every value matching `query`.
+    pub(crate) fn locate(&self, query: &str) -> Result<Vec<Vec<usize>>, String> {
+        let segments = parse_query(query)?;
+        Ok(run_query(self, &segments).into_iter().map(|(_, path)| path).collect())
+    }
+
+    /// Follows a path produced by [`RdnValue::locate`] mutably.
+    pub(crate) fn at_path_mut(&mut self, path: &[usize]) -> Option<&mut RdnValue> {
+        let Some((&position, rest)) = path.split_first() else {
+            return Some(self);
+        };
+        let child = match self {
+            RdnValue::Array(items) | RdnValue::Set(items) => items.get_mut(position),
+            RdnValue::Object(entries) => entries.get_mut(position).map(|(_, v)| v),
+            RdnValue::Map(entries) => entries.get_mut(position).map(|(_, v)| v),
+            _ => None,
+        };
+        child?.at_path_mut(rest)
+    }
+
+    /// Renders a path produced by [`RdnValue::locate`] in query syntax, e.g.
+    /// `$.users[0].created`.
+    pub(crate) fn describe_path(&self, path: &[usize]) -> String {
+        let mut out = String::from("$");
+        let mut node = self;
+        for &position in path {
+            push_path_step(&mut out, node, position);
+            node = children(node)[position];
+        }
+        out
+    }
+}
+
+/// Appends the query-syntax step from `parent` to its child at `position`:
+/// `.key` or `["key"]` for Object members and string-keyed Map entries, and
+/// `[n]` for everything else.
+pub(crate) fn push_path_step(out: &mut String, parent: &RdnValue, position: usize) {
+    let key = match parent {
+        RdnValue::Object(entries) => Some(&entries[position].0),
+        RdnValue::Map(entries) => match &entries[position].0 {
+            RdnValue::String(key) => Some(key),
+            _ => None,
+        },
+        _ => None,
+    };
+    match key {
+        Some(key) if !key.is_empty() && !key.contains(['.', '[', ']', '"', '\\', ' ']) => {
+            out.push('.');
+            out.push_str(key);
+        }
+        Some(key) => out.push_str(&format!("[{}]", RdnValue::String(key.clone()))),
+        None => out.push_str(&format!("[{position}]")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{n, obj};
+
+    fn sample() -> RdnValue {
+        obj(vec![
+            ("name", RdnValue::String("root".to_string())),
+            (
+                "servers",
+                RdnValue::Array(vec![
+                    obj(vec![("id", n(1.0)), ("port", n(80.0))]),
+                    obj(vec![("id", n(2.0)), ("port", n(443.0))]),
+                ]),
+            ),
+            ("meta", obj(vec![("id", n(99.0)), ("a.b", RdnValue::Bool(true))])),
+        ])
+    }
+
+    #[test]
+    fn select_root() {
+        let v = sample();
+        assert_eq!(v.select("$"), vec![&v]);
+    }
+
+    #[test]
+    fn select_dotted_keys() {
+        let v = sample();
+        assert_eq!(v.select("$.meta.id"), vec![&n(99.0)]);
+        assert!(v.select("$.meta.missing").is_empty());
+    }
+
+    #[test]
+    fn select_array_index() {
+        let v = sample();
+        assert_eq!(v.select("$.servers[1].port"), vec![&n(443.0)]);
+        assert!(v.select("$.servers[5]").is_empty());
+    }
+
+    #[test]
+    fn select_wildcard() {
+        let v = sample();
+        assert_eq!(v.select("$.servers[*].id"), vec![&n(1.0), &n(2.0)]);
+        assert_eq!(v.select("$.meta.*").len(), 2);
+    }
+
+    #[test]
+    fn select_recursive_descent() {
+        let v = sample();
+        assert_eq!(v.select("$..id"), vec![&n(1.0), &n(2.0), &n(99.0)]);
+    }
+
+    #[test]
+    fn select_quoted_key() {
+        let v = sample();
+        assert_eq!(v.select(r#"$.meta["a.b"]"#), vec![&RdnValue::Bool(true)]);
+    }
+
+    #[test]
+    fn select_map_string_keys() {
+        let v = RdnValue::Map(vec![
+            (n(1.0), RdnValue::Null),
+            (RdnValue::String("k".to_string()), n(7.0)),
+        ]);
+        assert_eq!(v.select("$.k"), vec![&n(7.0)]);
+    }
+
+    // --- get_path tests ---
+
+    #[test]
+    fn get_path_follows_keys_and_indices() {
+        let v = sample();
+        assert_eq!(v.get_path("servers[1].port"), Some(&n(443.0)));
+        assert_eq!(v.get_path("meta[\"a.b\"]"), Some(&RdnValue::Bool(true)));
+        assert_eq!(v.get_path("[\"name\"]"), Some(&RdnValue::String("root".to_string())));
+        assert_eq!(v.get_path(""), Some(&v));
+    }
+
+    #[test]
+    fn get_path_missing_or_wildcard_is_none() {
+        let v = sample();
+        assert_eq!(v.get_path("servers[5]"), None);
+        assert_eq!(v.get_path("servers[*].id"), None);
+        assert_eq!(v.get_path("..id"), None);
+        assert_eq!(v.get_path("servers["), None);
+    }
+
+    #[test]
+    fn has_path_matches_get_path() {
+        let v = sample();
+        assert!(v.has_path("meta.id"));
+        assert!(!v.has_path("meta.missing"));
+    }
+
+    #[test]
+    fn select_malformed_query_matches_nothing() {
+        let v = sample();
+        assert!(v.select("servers").is_empty());
+        assert!(v.select("$.servers[").is_empty());
+        assert!(v.select("$.servers[-1]").is_empty());
+        assert!(v.select("$.").is_empty());
+    }
+}
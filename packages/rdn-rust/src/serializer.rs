@@ -1,3 +1,10 @@
+use std::fmt::{self, Write};
+use std::io::{self, BufWriter, Write as _};
+
+use crate::date::{format_iso_datetime, format_iso_duration, MAX_DATE_MILLIS};
+use crate::encoding::{encode_base32, encode_base64, encode_hex};
+use crate::float::write_shortest;
+use crate::parser::is_identifier;
 use crate::types::*;
 
 /// Serialize an `RdnValue` to an RDN string.
@@ -11,16 +18,986 @@ use crate::types::*;
 /// - `Number` (±Infinity) → `Infinity` / `-Infinity`
 /// - `BigInt` → `42n`
 /// - `String` → `"escaped"`
-/// - `Date` → `@YYYY-MM-DDTHH:mm:ss.sssZ`
+/// - `Date` → `@YYYY-MM-DDTHH:mm:ss.sssZ` in UTC, with any sub-millisecond
+///   fraction rounded down
 /// - `RegExp` → `/pattern/flags`
-/// - `Binary` → `b"base64..."`
+/// - `Binary` → `b"base64..."`, or `x"hex..."` with [`BinaryEncoding::Hex`]
 /// - `Array` → `[...]`
 /// - `Object` → `{...}`
 /// - `Map` (non-empty) → `Map{k => v, ...}`
 /// - `Map` (empty) → `Map{}`
 /// - `Set` (non-empty) → `Set{v, ...}`
 /// - `Set` (empty) → `Set{}`
+/// - `TimeOnly` → `@HH:mm:ss`, with `.sss` if the milliseconds are non-zero
+/// - `Duration` → `@P...`, exactly as stored
+///
+/// The output is compact, with no whitespace between tokens, like the
+/// JavaScript implementation's.
+///
+/// `RdnValue` owns its children, so a value tree cannot contain a cycle and
+/// serialization always terminates without any cycle detection.
+///
+/// # Examples
+///
+/// ```
+/// use rdn::{parse, stringify};
+///
+/// assert_eq!(stringify(&parse("42n").unwrap()), "42n");
+/// ```
 pub fn stringify(value: &RdnValue) -> String {
-    // TODO: Implement serializer with cycle detection
-    todo!("Not implemented")
+    stringify_with(value, &StringifyOptions::default())
+}
+
+/// Options for [`stringify_with`]. The default gives the output of
+/// [`stringify`].
+#[derive(Debug, Clone, Default)]
+pub struct StringifyOptions {
+    /// The indent for each level of nesting. Unless it is [`Indent::None`]
+    /// or `Indent::Spaces(0)`, output is spread over lines as described at
+    /// [`stringify_pretty`]. `None` by default, for compact output.
+    pub indent: Indent,
+    /// The line break written between lines when indenting. `\n` by default.
+    pub newline: Newline,
+    /// Whether to write the entries of every Object, at any depth, in order
+    /// of their keys rather than as stored. Keys compare as strings do in
+    /// Rust, by Unicode code point; entries with the same key keep their
+    /// order. The value itself is not changed.
+    ///
+    /// Maps are always written in their stored order, even with all-string
+    /// keys: unlike an Object's, a Map's order is part of its value in
+    /// JavaScript, and its keys need not be comparable strings.
+    pub sort_keys: bool,
+    /// When to quote Object keys. Always by default, as JSON requires.
+    pub quote_keys: QuoteKeys,
+    /// Whether to write integer `Number`s beyond JavaScript's safe range,
+    /// with a magnitude over 2^53 − 1, as quoted strings of their digits,
+    /// so that a reader cannot silently round them: `1e20` is written as
+    /// `"100000000000000000000"`. The digits are those of the stored `f64`,
+    /// which may already have been rounded on the way in. Such values
+    /// reparse as Strings, so turning them back into numbers is up to the
+    /// reader. Off by default.
+    pub large_numbers_as_strings: bool,
+    /// A base instant to write every Date relative to, as the signed
+    /// Duration from it, such as `@PT1.5S` for a Date 1.5 seconds after the
+    /// base. Days count as exactly 24 hours, and one before the base gets a
+    /// leading `-`, `@-PT1.5S`.
+    ///
+    /// This is a display transform for logs and traces. It does not round
+    /// trip: the Dates reparse as Durations, and negative ones not at all,
+    /// since RDN's Duration literal has no sign. As when writing them whole,
+    /// both instants are rounded down to the millisecond, and a Date with no
+    /// ISO form is written as `null`, as is every Date if the base has none.
+    /// `None` by default.
+    pub date_relative_to: Option<RdnDate>,
+    /// How to write `Binary` values. Base64 by default.
+    pub binary_encoding: BinaryEncoding,
+    /// Whether to escape every non-ASCII character as `\uXXXX`, with a
+    /// surrogate pair for characters beyond the Basic Multilingual Plane, so
+    /// the output is pure ASCII. Off by default: output is UTF-8.
+    ///
+    /// Only three places can hold non-ASCII text, and each is escaped:
+    ///
+    /// - Strings and Object keys, which reparse to the same text.
+    /// - RegExp sources. `\uXXXX` matches the same character in JavaScript,
+    ///   with or without the `u` flag, but the reparsed source is spelled
+    ///   with the escape. An identity escape such as `\é` becomes `\u00e9`.
+    ///
+    /// Numbers, BigInts, Decimals, Dates, TimeOnly values and Binary are
+    /// always written in ASCII. A Duration is written as stored, which is
+    /// ASCII unless its `iso` field was set by hand, bypassing
+    /// [`RdnDuration::new`].
+    pub ensure_ascii: bool,
+}
+
+/// The indent for each level of nesting; see [`StringifyOptions::indent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Indent {
+    /// No line breaks or indentation: compact output.
+    #[default]
+    None,
+    /// This many spaces per level. `Spaces(0)` is the same as `None`, as
+    /// with JavaScript's `JSON.stringify`.
+    Spaces(usize),
+    /// One tab per level.
+    Tabs,
+}
+
+impl Indent {
+    /// The character repeated for each level and how many times, or `None`
+    /// for compact output.
+    fn unit(self) -> Option<(char, usize)> {
+        match self {
+            Indent::None | Indent::Spaces(0) => None,
+            Indent::Spaces(n) => Some((' ', n)),
+            Indent::Tabs => Some(('\t', 1)),
+        }
+    }
+}
+
+/// The line break used when indenting; see [`StringifyOptions::newline`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Newline {
+    /// `\n`.
+    #[default]
+    Lf,
+    /// `\r\n`, as Windows tools expect.
+    CrLf,
+}
+
+impl Newline {
+    fn as_str(self) -> &'static str {
+        match self {
+            Newline::Lf => "\n",
+            Newline::CrLf => "\r\n",
+        }
+    }
+}
+
+/// When Object keys are quoted; see [`StringifyOptions::quote_keys`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QuoteKeys {
+    /// Quote every key, `{"host": "x"}`, as JSON requires.
+    #[default]
+    Always,
+    /// Write a key bare, `{host: "x"}`, when it is an identifier as
+    /// [`ParseOptions::allow_unquoted_keys`](crate::ParseOptions::allow_unquoted_keys)
+    /// defines one: an ASCII letter, `_` or `$`, then any of those or ASCII
+    /// digits. Other keys, including the empty key, are quoted. Words such as
+    /// `true` or `null` are written bare too, since that parser reads any
+    /// identifier before a `:` as a key.
+    WhenNeeded,
+}
+
+/// The text form of a `Binary` value; see [`StringifyOptions::binary_encoding`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BinaryEncoding {
+    /// `b"..."`, padded standard base64.
+    #[default]
+    Base64,
+    /// `x"..."`, two lowercase hex digits per byte.
+    Hex,
+    /// `z"..."`, padded standard base32 (RFC 4648 §6). Only a parser with
+    /// [`ParseOptions::allow_base32_literals`](crate::ParseOptions::allow_base32_literals)
+    /// reads it back.
+    Base32,
+}
+
+/// Serialize an `RdnValue` to RDN text, formatted as `options` describe.
+///
+/// # Examples
+///
+/// ```
+/// use rdn::{parse, stringify_with, Indent, StringifyOptions};
+///
+/// let options = StringifyOptions { indent: Indent::Spaces(2), ..StringifyOptions::default() };
+/// assert_eq!(stringify_with(&parse("[1]").unwrap(), &options), "[\n  1\n]");
+/// ```
+pub fn stringify_with(value: &RdnValue, options: &StringifyOptions) -> String {
+    let mut out = String::new();
+    // Writing to a String cannot fail.
+    let _ = write_rdn(&mut out, value, options);
+    out
+}
+
+/// Writes `value` to `out` as [`stringify_with`] formats it.
+pub(crate) fn write_rdn(out: &mut impl Write, value: &RdnValue, options: &StringifyOptions) -> fmt::Result {
+    Serializer { out, options, level: 0 }.write_value(value)
+}
+
+/// Serialize an `RdnValue` as RDN text straight to `writer`, such as a file
+/// or socket, without building the whole text in memory first.
+///
+/// The output is exactly what [`stringify_with`] returns. Writes go through
+/// a [`BufWriter`], which is flushed before returning, so `writer` need not
+/// be buffered itself.
+///
+/// # Errors
+///
+/// Returns the first error from `writer`.
+///
+/// # Examples
+///
+/// ```
+/// use rdn::{parse, stringify_to_writer, StringifyOptions};
+///
+/// let mut out = Vec::new();
+/// stringify_to_writer(&parse("Set{1}").unwrap(), &mut out, &StringifyOptions::default()).unwrap();
+/// assert_eq!(out, b"Set{1}");
+/// ```
+pub fn stringify_to_writer<W: io::Write>(value: &RdnValue, writer: W, options: &StringifyOptions) -> io::Result<()> {
+    let mut adapter = IoAdapter { inner: BufWriter::new(writer), error: None };
+    let result = write_rdn(&mut adapter, value, options);
+    match (result, adapter.error) {
+        (Ok(()), _) => adapter.inner.flush(),
+        (Err(_), Some(e)) => Err(e),
+        // Only the adapter fails, and it always records why.
+        (Err(_), None) => Err(io::Error::other("formatter error")),
+    }
+}
+
+/// Lets the serializer write to an [`io::Write`], keeping the I/O error that
+/// [`fmt::Error`] cannot carry.
+struct IoAdapter<W: io::Write> {
+    inner: W,
+    error: Option<io::Error>,
+}
+
+impl<W: io::Write> Write for IoAdapter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|e| {
+            self.error = Some(e);
+            fmt::Error
+        })
+    }
+}
+
+/// Serialize an `RdnValue` to RDN text spread over lines, with each level of
+/// nesting indented by one more `indent`, and `\n` line breaks.
+///
+/// Every non-empty Array, Object, Map and Set puts each of its elements or
+/// entries on a line of its own; empty ones stay as `[]`, `{}`, `Map{}` and
+/// `Set{}`. Object keys are followed by `": "` and Map keys by `" => "`.
+/// Scalars are written as [`stringify`] writes them, and [`Indent::None`]
+/// gives exactly its compact output. This is [`stringify_with`] with
+/// [`StringifyOptions::indent`] set.
+///
+/// # Examples
+///
+/// ```
+/// use rdn::{parse, stringify_pretty, Indent};
+///
+/// let value = parse(r#"{"tags": Set{"a"}, "none": []}"#).unwrap();
+/// assert_eq!(stringify_pretty(&value, Indent::Spaces(2)), "{\n  \"tags\": Set{\n    \"a\"\n  },\n  \"none\": []\n}");
+/// ```
+pub fn stringify_pretty(value: &RdnValue, indent: Indent) -> String {
+    stringify_with(value, &StringifyOptions { indent, ..StringifyOptions::default() })
+}
+
+/// Writes `s` to `f` as a properly escaped RDN/JSON string (including the
+/// surrounding double quotes). With `ensure_ascii`, every non-ASCII character
+/// is escaped too, as a UTF-16 surrogate pair if need be.
+fn write_escaped_string(f: &mut impl fmt::Write, s: &str, ensure_ascii: bool) -> fmt::Result {
+    f.write_str("\"")?;
+    for ch in s.chars() {
+        match ch {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            '\u{08}' => f.write_str("\\b")?,
+            '\u{0C}' => f.write_str("\\f")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            // Valid in JSON strings but line terminators in JavaScript source, so
+            // they would break RDN embedded in a <script> block.
+            '\u{2028}' | '\u{2029}' => write!(f, "\\u{:04x}", ch as u32)?,
+            c if ensure_ascii && !c.is_ascii() => write_utf16_escape(f, c)?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_str("\"")
+}
+
+/// Writes a RegExp source, escaping non-ASCII characters if `ensure_ascii`.
+/// The source is otherwise written as stored, which [`RdnRegExp::new`] keeps
+/// valid between `/` delimiters.
+fn write_regexp_source(f: &mut impl fmt::Write, source: &str, ensure_ascii: bool) -> fmt::Result {
+    if !ensure_ascii || source.is_ascii() {
+        return f.write_str(source);
+    }
+    let mut chars = source.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            // `\` followed by a non-ASCII character is an identity escape,
+            // standing for the character itself.
+            '\\' => match chars.next() {
+                Some(c) if !c.is_ascii() => write_utf16_escape(f, c)?,
+                Some(c) => {
+                    f.write_char('\\')?;
+                    f.write_char(c)?;
+                }
+                None => f.write_char('\\')?,
+            },
+            c if !c.is_ascii() => write_utf16_escape(f, c)?,
+            c => f.write_char(c)?,
+        }
+    }
+    Ok(())
+}
+
+/// Writes `c` as `\uXXXX`, or a surrogate pair of them.
+fn write_utf16_escape(f: &mut impl fmt::Write, c: char) -> fmt::Result {
+    for unit in c.encode_utf16(&mut [0; 2]) {
+        write!(f, "\\u{unit:04x}")?;
+    }
+    Ok(())
+}
+
+/// Serializer state: where the output goes, the options in effect, and how
+/// many containers enclose the cursor.
+struct Serializer<'a, W> {
+    out: &'a mut W,
+    options: &'a StringifyOptions,
+    level: usize,
+}
+
+/// A container part-way through being written, kept on the serializer's
+/// stack.
+struct Frame<'v> {
+    parts: Parts<'v>,
+    close: &'static str,
+    /// Whether no part has been written yet.
+    first: bool,
+}
+
+/// The parts of a container still to be written.
+enum Parts<'v> {
+    Items(std::slice::Iter<'v, RdnValue>),
+    Entries(std::slice::Iter<'v, (String, RdnValue)>),
+    SortedEntries(std::vec::IntoIter<&'v (String, RdnValue)>),
+    MapEntries(std::slice::Iter<'v, (RdnValue, RdnValue)>),
+}
+
+/// An element of an Array or Set, or an entry of an Object or Map.
+enum Part<'v> {
+    Item(&'v RdnValue),
+    Entry(&'v str, &'v RdnValue),
+    MapEntry(&'v RdnValue, &'v RdnValue),
+}
+
+impl<'v> Iterator for Parts<'v> {
+    type Item = Part<'v>;
+
+    fn next(&mut self) -> Option<Part<'v>> {
+        match self {
+            Parts::Items(items) => items.next().map(Part::Item),
+            Parts::Entries(entries) => entries.next().map(|(key, value)| Part::Entry(key, value)),
+            Parts::SortedEntries(entries) => entries.next().map(|(key, value)| Part::Entry(key, value)),
+            Parts::MapEntries(entries) => entries.next().map(|(key, value)| Part::MapEntry(key, value)),
+        }
+    }
+}
+
+/// A piece of work waiting on the serializer's stack.
+enum Step<'v> {
+    Value(&'v RdnValue),
+    /// The `=>` between a Map key and its value.
+    Arrow,
+    /// The rest of a container.
+    Resume(Frame<'v>),
+}
+
+impl<W: Write> Serializer<'_, W> {
+    /// Writes `value` as RDN text.
+    ///
+    /// Open containers are kept on an explicit stack rather than the call
+    /// stack, so no value is too deeply nested to write.
+    fn write_value(&mut self, value: &RdnValue) -> fmt::Result {
+        let mut stack = vec![Step::Value(value)];
+        while let Some(step) = stack.pop() {
+            match step {
+                Step::Value(value) => {
+                    if let Some(frame) = self.write_start(value)? {
+                        stack.push(Step::Resume(frame));
+                    }
+                }
+                Step::Arrow => self.out.write_str(if self.is_compact() { "=>" } else { " => " })?,
+                Step::Resume(mut frame) => {
+                    let Some(part) = frame.parts.next() else {
+                        self.level -= 1;
+                        self.write_newline()?;
+                        self.out.write_str(frame.close)?;
+                        continue;
+                    };
+                    if !frame.first {
+                        self.out.write_char(',')?;
+                    }
+                    frame.first = false;
+                    self.write_newline()?;
+                    stack.push(Step::Resume(frame));
+                    match part {
+                        Part::Item(item) => stack.push(Step::Value(item)),
+                        Part::Entry(key, value) => {
+                            if self.options.quote_keys == QuoteKeys::WhenNeeded && is_identifier(key) {
+                                self.out.write_str(key)?;
+                            } else {
+                                write_escaped_string(self.out, key, self.options.ensure_ascii)?;
+                            }
+                            self.out.write_str(if self.is_compact() { ":" } else { ": " })?;
+                            stack.push(Step::Value(value));
+                        }
+                        Part::MapEntry(key, value) => {
+                            stack.push(Step::Value(value));
+                            stack.push(Step::Arrow);
+                            stack.push(Step::Value(key));
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes a scalar whole, or the opening of a container. Returns the
+    /// rest of a non-empty container, which is left open one level deeper.
+    fn write_start<'v>(&mut self, value: &'v RdnValue) -> Result<Option<Frame<'v>>, fmt::Error> {
+        let out = &mut *self.out;
+        let (open, parts, close, empty) = match value {
+            RdnValue::Null => return out.write_str("null").map(|()| None),
+            RdnValue::Bool(b) => return out.write_str(if *b { "true" } else { "false" }).map(|()| None),
+            RdnValue::Number(n) if self.options.large_numbers_as_strings && is_unsafe_integer(*n) => {
+                return write!(out, "\"{n}\"").map(|()| None);
+            }
+            RdnValue::Number(n) => return write_number(out, *n).map(|()| None),
+            RdnValue::BigInt(bi) => return write!(out, "{}n", bi.value()).map(|()| None),
+            RdnValue::Decimal(d) => return write!(out, "{}m", d.value()).map(|()| None),
+            RdnValue::String(s) => return write_escaped_string(out, s, self.options.ensure_ascii).map(|()| None),
+            // A sub-millisecond fraction rounds down, to the millisecond
+            // holding the instant. A Date that is not finite or is further
+            // from the epoch than a JavaScript Date may be has no ISO form,
+            // and like an invalid JavaScript Date is written as null. Relative
+            // to `date_relative_to`, any pair of i64 millis is written unless
+            // their difference overflows.
+            RdnValue::Date(date) => {
+                let floored = |date: &RdnDate| RdnDate { millis: date.millis.floor() }.millis_i64();
+                return match (floored(date), self.options.date_relative_to.as_ref()) {
+                    (Some(millis), None) if millis.abs() <= MAX_DATE_MILLIS => {
+                        write!(out, "@{}", format_iso_datetime(millis))
+                    }
+                    (Some(_), None) => out.write_str("null"),
+                    (Some(millis), Some(base)) => match floored(base) {
+                        Some(base) => match millis.checked_sub(base) {
+                            Some(diff) => write!(out, "@{}", format_iso_duration(diff)),
+                            None => out.write_str("null"),
+                        },
+                        None => out.write_str("null"),
+                    },
+                    (None, _) => out.write_str("null"),
+                }
+                .map(|()| None);
+            }
+            RdnValue::TimeOnly(t) => {
+                write!(out, "@{:02}:{:02}:{:02}", t.hours(), t.minutes(), t.seconds())?;
+                if t.milliseconds() > 0 {
+                    write!(out, ".{:03}", t.milliseconds())?;
+                }
+                return Ok(None);
+            }
+            RdnValue::Duration(d) => return write!(out, "@{}", d.iso).map(|()| None),
+            RdnValue::RegExp(re) => {
+                out.write_char('/')?;
+                write_regexp_source(out, re.source(), self.options.ensure_ascii)?;
+                return write!(out, "/{}", re.flags()).map(|()| None);
+            }
+            RdnValue::Binary(bytes) => {
+                match self.options.binary_encoding {
+                    BinaryEncoding::Base64 => {
+                        out.write_str("b\"")?;
+                        encode_base64(bytes, out)?;
+                    }
+                    BinaryEncoding::Hex => {
+                        out.write_str("x\"")?;
+                        encode_hex(bytes, out)?;
+                    }
+                    BinaryEncoding::Base32 => {
+                        out.write_str("z\"")?;
+                        encode_base32(bytes, out)?;
+                    }
+                }
+                return out.write_char('"').map(|()| None);
+            }
+            RdnValue::Array(items) => ("[", Parts::Items(items.iter()), "]", items.is_empty()),
+            RdnValue::Object(entries) if self.options.sort_keys => {
+                let mut sorted: Vec<_> = entries.iter().collect();
+                sorted.sort_by(|(a, _), (b, _)| a.cmp(b));
+                ("{", Parts::SortedEntries(sorted.into_iter()), "}", entries.is_empty())
+            }
+            RdnValue::Object(entries) => ("{", Parts::Entries(entries.iter()), "}", entries.is_empty()),
+            RdnValue::Map(entries) => ("Map{", Parts::MapEntries(entries.iter()), "}", entries.is_empty()),
+            RdnValue::Set(items) => ("Set{", Parts::Items(items.iter()), "}", items.is_empty()),
+        };
+        out.write_str(open)?;
+        if empty {
+            out.write_str(close)?;
+            return Ok(None);
+        }
+        self.level += 1;
+        Ok(Some(Frame { parts, close, first: true }))
+    }
+
+    /// Whether output stays on one line, with no spaces between tokens.
+    fn is_compact(&self) -> bool {
+        self.options.indent.unit().is_none()
+    }
+
+    /// Starts a new line at the current level, if indenting.
+    fn write_newline(&mut self) -> fmt::Result {
+        let Some((unit, width)) = self.options.indent.unit() else {
+            return Ok(());
+        };
+        self.out.write_str(self.options.newline.as_str())?;
+        for _ in 0..self.level * width {
+            self.out.write_char(unit)?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether `n` is an integer too large in magnitude for every integer near
+/// it to be exactly representable, as in JavaScript's
+/// `!Number.isSafeInteger(n)`.
+fn is_unsafe_integer(n: f64) -> bool {
+    const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_991.0;
+    n.is_finite() && n.fract() == 0.0 && n.abs() > MAX_SAFE_INTEGER
+}
+
+fn write_number(out: &mut impl Write, n: f64) -> fmt::Result {
+    if n.is_nan() {
+        out.write_str("NaN")
+    } else if n.is_infinite() {
+        out.write_str(if n > 0.0 { "Infinity" } else { "-Infinity" })
+    } else {
+        write_shortest(out, n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse, parse_with, ParseOptions};
+
+    fn decimals() -> ParseOptions {
+        ParseOptions { allow_decimal_literals: true, ..ParseOptions::default() }
+    }
+
+    fn round_trip(input: &str) {
+        assert_eq!(stringify(&parse_with(input, &decimals()).unwrap()), input);
+    }
+
+    // --- Scalar tests ---
+
+    #[test]
+    fn stringify_literals() {
+        round_trip("null");
+        round_trip("true");
+        round_trip("false");
+    }
+
+    #[test]
+    fn stringify_numbers() {
+        for input in ["0", "-0", "42", "-17", "3.25", "-0.001", "123456789012", "NaN", "Infinity", "-Infinity"] {
+            round_trip(input);
+        }
+        for input in ["1e+21", "1e+300", "5e-324", "-1.5e-7", "0.30000000000000004"] {
+            round_trip(input);
+        }
+        assert_eq!(stringify(&RdnValue::Number(1.5e3)), "1500");
+        assert_eq!(stringify(&RdnValue::Number(1e300)), "1e+300");
+    }
+
+    #[test]
+    fn stringify_bigint_and_decimal() {
+        round_trip("42n");
+        round_trip("-123456789012345678901234567890n");
+        round_trip("3.14m");
+    }
+
+    #[test]
+    fn stringify_strings() {
+        round_trip(r#""""#);
+        round_trip(r#""hello""#);
+        round_trip(r#""say \"hi\"\\ \n\t\r\b\f""#);
+        round_trip(r#""\u0001\u2028""#);
+        round_trip("\"caf\u{e9} \u{1F600}\"");
+        assert_eq!(stringify(&parse(r#""A\/""#).unwrap()), r#""A/""#);
+    }
+
+    // --- StringifyOptions tests ---
+
+    #[test]
+    fn default_options_match_stringify() {
+        let value = parse(NESTED).unwrap();
+        assert_eq!(stringify_with(&value, &StringifyOptions::default()), stringify(&value));
+    }
+
+    #[test]
+    fn indent_option() {
+        let value = parse(r#"{"a": [1]}"#).unwrap();
+        let options = StringifyOptions { indent: Indent::Spaces(2), ..StringifyOptions::default() };
+        assert_eq!(stringify_with(&value, &options), "{\n  \"a\": [\n    1\n  ]\n}");
+        assert_eq!(stringify_with(&value, &options), stringify_pretty(&value, Indent::Spaces(2)));
+    }
+
+    #[test]
+    fn sort_keys_option() {
+        let value = parse(r#"{"b": 1, "a": {"z": 2, "y": [{"d": 3, "c": 4}]}, "A": Map{"q" => 5, "p" => 6}}"#).unwrap();
+        let original = value.clone();
+        let options = StringifyOptions { sort_keys: true, ..StringifyOptions::default() };
+        let sorted = r#"{"A":Map{"q"=>5,"p"=>6},"a":{"y":[{"c":4,"d":3}],"z":2},"b":1}"#;
+        assert_eq!(stringify_with(&value, &options), sorted);
+        assert_eq!(value, original);
+        assert_eq!(stringify(&value), r#"{"b":1,"a":{"z":2,"y":[{"d":3,"c":4}]},"A":Map{"q"=>5,"p"=>6}}"#);
+    }
+
+    #[test]
+    fn sort_keys_keeps_duplicate_order() {
+        let value = parse(r#"{"b": 1, "a": 2, "b": 3}"#).unwrap();
+        let options = StringifyOptions { sort_keys: true, ..StringifyOptions::default() };
+        assert_eq!(stringify_with(&value, &options), r#"{"a":2,"b":1,"b":3}"#);
+    }
+
+    #[test]
+    fn binary_encoding_option() {
+        let value = RdnValue::Binary(vec![0x00, 0xAB, 0xFF, 0x10]);
+        let hex = StringifyOptions { binary_encoding: BinaryEncoding::Hex, ..StringifyOptions::default() };
+        assert_eq!(stringify(&value), r#"b"AKv/EA==""#);
+        assert_eq!(stringify_with(&value, &hex), r#"x"00abff10""#);
+        assert_eq!(parse(&stringify(&value)).unwrap(), value);
+        assert_eq!(parse(&stringify_with(&value, &hex)).unwrap(), value);
+        assert_eq!(stringify_with(&RdnValue::Binary(Vec::new()), &hex), r#"x"""#);
+        let base32 = StringifyOptions { binary_encoding: BinaryEncoding::Base32, ..StringifyOptions::default() };
+        assert_eq!(stringify_with(&value, &base32), r#"z"ACV76EA=""#);
+        let options = crate::ParseOptions { allow_base32_literals: true, ..crate::ParseOptions::default() };
+        assert_eq!(crate::parse_with(&stringify_with(&value, &base32), &options).unwrap(), value);
+    }
+
+    #[test]
+    fn ensure_ascii_option() {
+        let value = parse("{\"caf\u{e9}\": [\"\u{1F600} \u{4e2d}\", \"\u{2028}\"]}").unwrap();
+        let ascii = StringifyOptions { ensure_ascii: true, ..StringifyOptions::default() };
+        assert_eq!(stringify(&value), "{\"caf\u{e9}\":[\"\u{1F600} \u{4e2d}\",\"\\u2028\"]}");
+        let escaped = stringify_with(&value, &ascii);
+        assert_eq!(escaped, r#"{"caf\u00e9":["\ud83d\ude00 \u4e2d","\u2028"]}"#);
+        assert!(escaped.is_ascii());
+        assert_eq!(parse(&escaped).unwrap(), value);
+    }
+
+    #[test]
+    fn ensure_ascii_covers_whole_output() {
+        let value = parse_with(concat!(
+            "{\"k\u{e9}y\": Map{\"\u{4e2d}\" => Set{/caf\u{e9}[\u{1F600}]+\\\u{e9}\\d/u}}, ",
+            "\"rest\": [42n, 3.5m, @2024-01-15T10:30:00.000Z, @14:30:00, @PT1H, b\"AQID\", -Infinity]}",
+        ), &decimals())
+        .unwrap();
+        let ascii = StringifyOptions { ensure_ascii: true, ..StringifyOptions::default() };
+        let text = stringify_with(&value, &ascii);
+        assert!(text.is_ascii(), "{text}");
+        assert!(text.contains(r"/caf\u00e9[\ud83d\ude00]+\u00e9\d/u"), "{text}");
+        let pretty = StringifyOptions { indent: Indent::Tabs, ..ascii.clone() };
+        assert!(stringify_with(&value, &pretty).is_ascii());
+        // The RegExp reparses with its non-ASCII characters spelled as escapes.
+        let reparsed = parse_with(&text, &decimals()).unwrap();
+        assert_eq!(reparsed["rest"], value["rest"]);
+        assert_eq!(stringify_with(&reparsed, &ascii), text);
+    }
+
+    #[test]
+    fn large_numbers_as_strings_option() {
+        let value = parse("[9007199254740991, 9007199254740992, -9007199254740993, 1e20, 2.5, NaN, 1e400]").unwrap();
+        let options = StringifyOptions { large_numbers_as_strings: true, ..StringifyOptions::default() };
+        assert_eq!(
+            stringify_with(&value, &options),
+            r#"[9007199254740991,"9007199254740992","-9007199254740992","100000000000000000000",2.5,NaN,Infinity]"#
+        );
+        assert_eq!(stringify(&value), "[9007199254740991,9007199254740992,-9007199254740992,100000000000000000000,2.5,NaN,Infinity]");
+        assert_eq!(stringify_with(&parse("Map{1e16 => 1}").unwrap(), &options), r#"Map{"10000000000000000"=>1}"#);
+    }
+
+    #[test]
+    fn quote_keys_option() {
+        let value = parse(r#"{"host": "x", "$id_2": {"true": 1, "a-b": 2, "": 3, "9x": 4, "caf\u00e9": 5}}"#).unwrap();
+        let bare = StringifyOptions { quote_keys: QuoteKeys::WhenNeeded, ..StringifyOptions::default() };
+        let text = stringify_with(&value, &bare);
+        assert_eq!(text, r#"{host:"x",$id_2:{true:1,"a-b":2,"":3,"9x":4,"café":5}}"#);
+        assert_eq!(stringify(&value), r#"{"host":"x","$id_2":{"true":1,"a-b":2,"":3,"9x":4,"café":5}}"#);
+        assert!(parse(&text).is_err());
+        let unquoted = crate::ParseOptions { allow_unquoted_keys: true, ..crate::ParseOptions::default() };
+        assert_eq!(crate::parse_with(&text, &unquoted).unwrap(), value);
+        let pretty = StringifyOptions { indent: Indent::Spaces(2), ..bare };
+        assert_eq!(crate::parse_with(&stringify_with(&value, &pretty), &unquoted).unwrap(), value);
+    }
+
+    // --- Writer tests ---
+
+    /// Accepts `capacity` bytes, then fails.
+    struct Limited {
+        written: Vec<u8>,
+        capacity: usize,
+    }
+
+    impl io::Write for Limited {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if self.written.len() + buf.len() > self.capacity {
+                return Err(io::Error::new(io::ErrorKind::WriteZero, "disk full"));
+            }
+            self.written.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn stringify_to_writer_matches_stringify() {
+        let value = parse(NESTED).unwrap();
+        let pretty = StringifyOptions { indent: Indent::Spaces(2), sort_keys: true, ..StringifyOptions::default() };
+        for options in [StringifyOptions::default(), pretty] {
+            let mut out = Vec::new();
+            stringify_to_writer(&value, &mut out, &options).unwrap();
+            assert_eq!(String::from_utf8(out).unwrap(), stringify_with(&value, &options));
+        }
+    }
+
+    #[test]
+    fn stringify_to_writer_reports_io_errors() {
+        let value = RdnValue::String("x".repeat(10_000));
+        let writer = Limited { written: Vec::new(), capacity: 100 };
+        let err = stringify_to_writer(&value, writer, &StringifyOptions::default()).unwrap_err();
+        assert_eq!(err.to_string(), "disk full");
+    }
+
+    #[test]
+    fn stringify_very_deep_value() {
+        // Shallow enough for the value's own recursive drop on this thread,
+        // but far too deep to serialize recursively on a 64 KiB stack.
+        const DEPTH: usize = 3_001;
+        let mut value = RdnValue::Number(0.0);
+        for i in 0..DEPTH {
+            value = match i % 3 {
+                0 => RdnValue::Array(vec![value]),
+                1 => RdnValue::Set(vec![value]),
+                _ => RdnValue::Map(vec![(RdnValue::Null, value)]),
+            };
+        }
+        let text = std::thread::scope(|scope| {
+            let small_stack = std::thread::Builder::new().stack_size(64 * 1024);
+            small_stack.spawn_scoped(scope, || stringify(&value)).unwrap().join().unwrap()
+        });
+        assert_eq!(text.len(), DEPTH / 3 * ("[]".len() + "Set{}".len() + "Map{null=>}".len()) + "[]0".len());
+        assert!(text.starts_with("[Map{null=>Set{[Map{null=>"));
+        assert!(text.contains("[Map{null=>Set{[0]}}]}}]") && text.ends_with("]}}]"));
+    }
+
+    // --- Extended type tests ---
+
+    #[test]
+    fn stringify_dates_and_times() {
+        round_trip("@2024-01-15T10:30:00.000Z");
+        round_trip("@1969-12-31T23:59:59.999Z");
+        assert_eq!(stringify(&parse("@2024-01-15").unwrap()), "@2024-01-15T00:00:00.000Z");
+        round_trip("@0001-01-01T00:00:00.000Z");
+        round_trip("@9999-12-31T23:59:59.999Z");
+        round_trip("@14:30:00");
+        round_trip("@00:00:00.050");
+        round_trip("@P1Y2M3DT4H5M6.5S");
+    }
+
+    #[test]
+    fn stringify_dates_canonically() {
+        let date = |millis: f64| stringify(&RdnValue::Date(RdnDate { millis }));
+        assert_eq!(date(0.0), "@1970-01-01T00:00:00.000Z");
+        assert_eq!(date(1_705_314_600_042.0), "@2024-01-15T10:30:00.042Z");
+        assert_eq!(date(-86_400_001.0), "@1969-12-30T23:59:59.999Z");
+        assert_eq!(date(-14_182_940_000_000.0), "@1520-07-24T14:26:40.000Z");
+        assert_eq!(date(1.9), "@1970-01-01T00:00:00.001Z");
+        assert_eq!(date(-0.5), "@1969-12-31T23:59:59.999Z");
+        assert_eq!(date(f64::NAN), "null");
+        assert_eq!(date(f64::INFINITY), "null");
+        assert_eq!(date(253_402_300_800_000.0), "@+010000-01-01T00:00:00.000Z");
+        assert_eq!(date(-62_167_219_200_001.0), "@-000001-12-31T23:59:59.999Z");
+        assert_eq!(date(8.64e15 + 1.0), "null");
+        assert_eq!(date(-8.64e15 - 1.0), "null");
+        let boundaries = [-8.64e15, -62_167_219_200_001.0, -62_167_219_200_000.0, 253_402_300_799_999.0, 253_402_300_800_000.0, 8.64e15];
+        for millis in [0.0, 1_705_314_600_042.0, -86_400_001.0, -14_182_940_000_000.0].into_iter().chain(boundaries) {
+            let parsed = parse(&date(millis)).unwrap();
+            assert_eq!(parsed, RdnValue::Date(RdnDate { millis }));
+        }
+    }
+
+    #[test]
+    fn date_relative_to_option() {
+        let base = RdnDate::parse_iso("2024-01-15T10:30:00.000Z").unwrap();
+        let options = StringifyOptions { date_relative_to: Some(base), ..StringifyOptions::default() };
+        let value = parse(concat!(
+            "[@2024-01-15T10:30:01.500Z, @2024-01-15T10:30:00.000Z, @2024-01-16T12:31:02.000Z, ",
+            "@2024-01-15T10:29:58.500Z, @14:30:00]",
+        ))
+        .unwrap();
+        assert_eq!(stringify_with(&value, &options), "[@PT1.5S,@PT0S,@P1DT2H1M2S,@-PT1.5S,@14:30:00]");
+        let later = RdnValue::Array(vec![RdnValue::Date(RdnDate { millis: 1_705_314_601_500.9 })]);
+        assert_eq!(parse(&stringify_with(&later, &options)).unwrap(), parse("[@PT1.5S]").unwrap());
+        let invalid = StringifyOptions { date_relative_to: Some(RdnDate { millis: f64::NAN }), ..StringifyOptions::default() };
+        assert_eq!(stringify_with(&later, &invalid), "[null]");
+        let far = StringifyOptions { date_relative_to: Some(RdnDate { millis: -9e18 }), ..StringifyOptions::default() };
+        assert_eq!(stringify_with(&RdnValue::Date(RdnDate { millis: 9e18 }), &far), "null");
+        assert_eq!(stringify_with(&RdnValue::Date(RdnDate { millis: -9e18 }), &far), "@PT0S");
+    }
+
+    #[test]
+    fn stringify_regexp_and_binary() {
+        round_trip(r"/^a\/b[0-9]+$/gi");
+        round_trip(r#"b"SGVsbG8=""#);
+        round_trip(r#"b"""#);
+        assert_eq!(stringify(&parse(r#"x"48656c6c6f""#).unwrap()), r#"b"SGVsbG8=""#);
+    }
+
+    // --- Container tests ---
+
+    const NESTED: &str = r#"{"name":"RDN","tags":Set{"a","b"},"meta":{"empty":[],"map":Map{1=>[true,{}]}},"n":1}"#;
+
+    #[test]
+    fn stringify_pretty_two_spaces() {
+        let expected = concat!(
+            "{\n",
+            "  \"name\": \"RDN\",\n",
+            "  \"tags\": Set{\n",
+            "    \"a\",\n",
+            "    \"b\"\n",
+            "  },\n",
+            "  \"meta\": {\n",
+            "    \"empty\": [],\n",
+            "    \"map\": Map{\n",
+            "      1 => [\n",
+            "        true,\n",
+            "        {}\n",
+            "      ]\n",
+            "    }\n",
+            "  },\n",
+            "  \"n\": 1\n",
+            "}",
+        );
+        let value = parse(NESTED).unwrap();
+        assert_eq!(stringify_pretty(&value, Indent::Spaces(2)), expected);
+        assert_eq!(parse(expected).unwrap(), value);
+    }
+
+    #[test]
+    fn stringify_pretty_tabs() {
+        let value = parse(r#"[1, {"a": Set{}}, Map{}]"#).unwrap();
+        assert_eq!(stringify_pretty(&value, Indent::Tabs), "[\n\t1,\n\t{\n\t\t\"a\": Set{}\n\t},\n\tMap{}\n]");
+    }
+
+    #[test]
+    fn indent_applies_at_every_level() {
+        let value = parse(r#"[Map{"k" => Set{{"a": [1]}}}]"#).unwrap();
+        let expected = concat!(
+            "[\n", " Map{\n", "  \"k\" => Set{\n", "   {\n", "    \"a\": [\n",
+            "     1\n", "    ]\n", "   }\n", "  }\n", " }\n", "]",
+        );
+        assert_eq!(stringify_pretty(&value, Indent::Spaces(1)), expected);
+        for (indent, unit) in [(Indent::Spaces(4), "    "), (Indent::Tabs, "\t")] {
+            let lines: Vec<String> = expected
+                .lines()
+                .map(|line| {
+                    let body = line.trim_start_matches(' ');
+                    unit.repeat(line.len() - body.len()) + body
+                })
+                .collect();
+            assert_eq!(stringify_pretty(&value, indent), lines.join("\n"));
+        }
+    }
+
+    #[test]
+    fn newline_option() {
+        let value = parse(r#"{"a": Map{1 => Set{2}}}"#).unwrap();
+        let crlf = StringifyOptions { indent: Indent::Tabs, newline: Newline::CrLf, ..StringifyOptions::default() };
+        let text = stringify_with(&value, &crlf);
+        assert_eq!(text, "{\r\n\t\"a\": Map{\r\n\t\t1 => Set{\r\n\t\t\t2\r\n\t\t}\r\n\t}\r\n}");
+        assert_eq!(parse(&text).unwrap(), value);
+        let compact = StringifyOptions { newline: Newline::CrLf, ..StringifyOptions::default() };
+        assert_eq!(stringify_with(&value, &compact), stringify(&value));
+    }
+
+    #[test]
+    fn stringify_pretty_scalars_and_compact() {
+        assert_eq!(stringify_pretty(&RdnValue::Number(42.0), Indent::Spaces(2)), "42");
+        assert_eq!(stringify_pretty(&RdnValue::String("a\nb".to_string()), Indent::Spaces(2)), r#""a\nb""#);
+        assert_eq!(stringify_pretty(&RdnValue::Array(Vec::new()), Indent::Spaces(2)), "[]");
+        let value = parse(NESTED).unwrap();
+        assert_eq!(stringify_pretty(&value, Indent::None), NESTED);
+        assert_eq!(stringify_pretty(&value, Indent::Spaces(0)), NESTED);
+    }
+
+    #[test]
+    fn map_order_survives_round_trip() {
+        let input = r#"Map{"z"=>1,"a"=>2,3=>"m",[0]=>null,"b"=>Map{"y"=>1,"x"=>2}}"#;
+        let value = parse(input).unwrap();
+        let RdnValue::Map(entries) = &value else { panic!("not a Map: {value:?}") };
+        assert_eq!(entries[0].0, RdnValue::String("z".to_string()));
+        assert_eq!(entries[1].0, RdnValue::String("a".to_string()));
+        let text = stringify(&value);
+        assert_eq!(text, input);
+        assert_eq!(parse(&text).unwrap(), value);
+        // Unlike Object entries, Map entries are never sorted.
+        let sorted = StringifyOptions { sort_keys: true, ..StringifyOptions::default() };
+        assert_eq!(stringify_with(&value, &sorted), input);
+    }
+
+    #[test]
+    fn stringify_empty_containers() {
+        assert_eq!(stringify(&RdnValue::Array(Vec::new())), "[]");
+        assert_eq!(stringify(&RdnValue::Object(Vec::new())), "{}");
+        assert_eq!(stringify(&RdnValue::Map(Vec::new())), "Map{}");
+        assert_eq!(stringify(&RdnValue::Set(Vec::new())), "Set{}");
+    }
+
+    #[test]
+    fn stringify_every_variant() {
+        let input = concat!(
+            r#"{"null":null,"bool":[true,false],"num":[1.5,NaN,-Infinity],"big":42n,"dec":3.14m,"#,
+            r#""str":"a\"b","date":@2024-01-15T10:30:00.000Z,"time":@14:30:00.500,"dur":@PT1H,"#,
+            r#""re":/x+/g,"bin":b"AQID","map":Map{1=>"one",[2]=>Set{}},"set":Set{"a",{"k":Map{}}},"#,
+            r#""empty":[[],{}]}"#
+        );
+        round_trip(input);
+    }
+
+    // --- RegExp round-trip tests ---
+
+    mod regex_roundtrip {
+        use super::*;
+
+        fn assert_round_trips(source: &str, flags: &str) -> String {
+            let value = RdnValue::RegExp(RdnRegExp::new(source, flags).unwrap());
+            let text = stringify(&value);
+            assert_eq!(parse(&text).unwrap(), value, "{text}");
+            text
+        }
+
+        #[test]
+        fn slash_in_source() {
+            assert_eq!(assert_round_trips("a/b", "g"), r"/a\/b/g");
+            assert_eq!(assert_round_trips("/", ""), r"/\//");
+            assert_eq!(assert_round_trips("^https?://", "i"), r"/^https?:\/\//i");
+        }
+
+        #[test]
+        fn already_escaped_slash() {
+            assert_eq!(assert_round_trips(r"a\/b", ""), r"/a\/b/");
+            assert_eq!(assert_round_trips(r"a\\/b", ""), r"/a\\\/b/");
+        }
+
+        #[test]
+        fn slash_in_character_class() {
+            assert_eq!(assert_round_trips("[a/b]", ""), "/[a/b]/");
+            assert_eq!(assert_round_trips(r"[\]/]+/x", "u"), r"/[\]/]+\/x/u");
+            assert_eq!(parse("/[a/b]/").unwrap(), RdnValue::RegExp(RdnRegExp::new("[a/b]", "").unwrap()));
+        }
+
+        #[test]
+        fn trailing_backslash() {
+            assert_eq!(assert_round_trips(r"a\\", ""), r"/a\\/");
+            assert_eq!(assert_round_trips(r"\\", "g"), r"/\\/g");
+            // A lone trailing backslash would escape the closing delimiter.
+            assert!(RdnRegExp::new("a\\", "").is_err());
+        }
+
+        #[test]
+        fn empty_pattern() {
+            assert_eq!(assert_round_trips("", "g"), "/(?:)/g");
+            assert!(parse("//").is_err());
+        }
+    }
 }
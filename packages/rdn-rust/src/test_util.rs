@@ -0,0 +1,15 @@
+//! Value constructors shared by the unit tests.
+
+use crate::RdnValue;
+
+pub(crate) fn s(v: &str) -> RdnValue {
+    RdnValue::String(v.to_string())
+}
+
+pub(crate) fn n(v: f64) -> RdnValue {
+    RdnValue::Number(v)
+}
+
+pub(crate) fn obj(entries: Vec<(&str, RdnValue)>) -> RdnValue {
+    RdnValue::Object(entries.into_iter().map(|(k, v)| (k.to_string(), v)).collect())
+}
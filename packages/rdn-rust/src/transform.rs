@@ -0,0 +1,552 @@
+use crate::types::*;
+
+/// A Unicode normalization form, for [`RdnValue::normalize_unicode`].
+#[cfg(feature = "unicode-normalization")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NormalizationForm {
+    /// Canonical decomposition followed by canonical composition.
+    Nfc,
+    /// Canonical decomposition.
+    Nfd,
+    /// Compatibility decomposition followed by canonical composition.
+    Nfkc,
+    /// Compatibility decomposition.
+    Nfkd,
+}
+
+#[cfg(feature = "unicode-normalization")]
+impl NormalizationForm {
+    fn apply(self, s: &mut String) {
+        use unicode_normalization::UnicodeNormalization;
+        let normalized: String = match self {
+            NormalizationForm::Nfc => s.nfc().collect(),
+            NormalizationForm::Nfd => s.nfd().collect(),
+            NormalizationForm::Nfkc => s.nfkc().collect(),
+            NormalizationForm::Nfkd => s.nfkd().collect(),
+        };
+        *s = normalized;
+    }
+}
+
+/// Limits and markers for [`RdnValue::preview`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreviewOptions {
+    /// Elements kept from each Array and Set. Defaults to 10.
+    pub max_array_elems: usize,
+    /// Entries kept from each Object and Map. Defaults to 10.
+    pub max_object_keys: usize,
+    /// Characters kept from each String value and Object key. Defaults to 80.
+    pub max_string_len: usize,
+    /// Stands in for dropped elements or entries, with `{n}` replaced by how
+    /// many were dropped. Defaults to `…{n} more`.
+    pub more_marker: String,
+    /// Appended to a clipped string. Defaults to `…`.
+    pub clip_marker: String,
+}
+
+impl Default for PreviewOptions {
+    fn default() -> Self {
+        PreviewOptions {
+            max_array_elems: 10,
+            max_object_keys: 10,
+            max_string_len: 80,
+            more_marker: "…{n} more".to_string(),
+            clip_marker: "…".to_string(),
+        }
+    }
+}
+
+impl PreviewOptions {
+    fn more(&self, dropped: usize) -> String {
+        self.more_marker.replace("{n}", &dropped.to_string())
+    }
+
+    fn clip(&self, s: &str) -> String {
+        match s.char_indices().nth(self.max_string_len) {
+            Some((end, _)) => format!("{}{}", &s[..end], self.clip_marker),
+            None => s.to_string(),
+        }
+    }
+}
+
+impl RdnValue {
+    /// Rewrites every `Number` in the tree into its canonical form, in place.
+    ///
+    /// Because `Number` holds an `f64`, source spellings such as `42`, `42.0`,
+    /// and `4.2e1` already parse to the same value and serialize as `42`; the
+    /// original text is discarded in favor of the canonical form. This pass
+    /// removes the distinctions that survive in the `f64` itself: `-0` becomes
+    /// `0`, and every NaN payload becomes the canonical quiet NaN, so
+    /// semantically equal documents end up bit-identical. It recurses through
+    /// all containers, including Map keys and Set members.
+    pub fn normalize_numbers(&mut self) {
+        match self {
+            RdnValue::Number(n) => {
+                if n.is_nan() {
+                    *n = f64::NAN;
+                } else if *n == 0.0 {
+                    *n = 0.0;
+                }
+            }
+            RdnValue::Array(items) | RdnValue::Set(items) => items.iter_mut().for_each(RdnValue::normalize_numbers),
+            RdnValue::Object(entries) => entries.iter_mut().for_each(|(_, v)| v.normalize_numbers()),
+            RdnValue::Map(entries) => entries.iter_mut().for_each(|(k, v)| {
+                k.normalize_numbers();
+                v.normalize_numbers();
+            }),
+            _ => {}
+        }
+    }
+
+    /// Removes duplicate `Set` members and duplicate `Map` keys throughout the
+    /// tree, in place.
+    ///
+    /// Duplicates are found with structural equality, after nested containers
+    /// have themselves been deduplicated. `==` compares Numbers with
+    /// JavaScript's SameValueZero, as JS `Set` and `Map` do: `0` matches `-0`
+    /// and `NaN` matches `NaN`, so `Set{NaN, NaN}` collapses to
+    /// `Set{NaN}` and `NaN` works as a single Map key. Set members keep their
+    /// first occurrence. Map entries keep the position of the first occurrence
+    /// of a key but take the value of the last one (last wins), matching
+    /// repeated `Map.prototype.set` calls in JavaScript. Arrays and Objects are
+    /// only recursed into, never deduplicated.
+    pub fn deduplicate_sets(&mut self) {
+        match self {
+            RdnValue::Array(items) => items.iter_mut().for_each(RdnValue::deduplicate_sets),
+            RdnValue::Object(entries) => entries.iter_mut().for_each(|(_, v)| v.deduplicate_sets()),
+            RdnValue::Set(members) => {
+                members.iter_mut().for_each(RdnValue::deduplicate_sets);
+                let mut unique: Vec<RdnValue> = Vec::with_capacity(members.len());
+                for member in members.drain(..) {
+                    if !unique.contains(&member) {
+                        unique.push(member);
+                    }
+                }
+                *members = unique;
+            }
+            RdnValue::Map(entries) => {
+                let mut unique: Vec<(RdnValue, RdnValue)> = Vec::with_capacity(entries.len());
+                for (mut key, mut value) in entries.drain(..) {
+                    key.deduplicate_sets();
+                    value.deduplicate_sets();
+                    match unique.iter_mut().find(|(k, _)| *k == key) {
+                        Some(existing) => existing.1 = value,
+                        None => unique.push((key, value)),
+                    }
+                }
+                *entries = unique;
+            }
+            _ => {}
+        }
+    }
+
+    /// Removes `Null`-valued Object and Map entries throughout the tree, in
+    /// place.
+    ///
+    /// With `include_arrays`, `Null` elements of Arrays and Sets are removed
+    /// too; this is opt-in because it shifts the indices of later elements.
+    /// Remaining values are recursed into, so nested nulls are pruned as well.
+    /// Map keys are left untouched, and a container emptied by pruning is kept.
+    pub fn prune_nulls(&mut self, include_arrays: bool) {
+        match self {
+            RdnValue::Array(items) | RdnValue::Set(items) => {
+                if include_arrays {
+                    items.retain(|item| !matches!(item, RdnValue::Null));
+                }
+                items.iter_mut().for_each(|item| item.prune_nulls(include_arrays));
+            }
+            RdnValue::Object(entries) => {
+                entries.retain(|(_, v)| !matches!(v, RdnValue::Null));
+                entries.iter_mut().for_each(|(_, v)| v.prune_nulls(include_arrays));
+            }
+            RdnValue::Map(entries) => {
+                entries.retain(|(_, v)| !matches!(v, RdnValue::Null));
+                entries.iter_mut().for_each(|(_, v)| v.prune_nulls(include_arrays));
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns a summarized deep clone for displaying or logging a large
+    /// document: long containers are cut short and long strings clipped,
+    /// according to `options`.
+    ///
+    /// An Array or Set keeps its first elements and ends with a String
+    /// element holding [`PreviewOptions::more_marker`]. An Object or Map keeps
+    /// its first entries and ends with an entry whose key is the marker and
+    /// whose value is `Null`. Strings and Object keys longer than the limit
+    /// are cut and end with [`PreviewOptions::clip_marker`]. Map keys are
+    /// previewed like any other value; Binary data is left whole.
+    ///
+    /// The result is a valid value tree, but a lossy one: the markers are
+    /// ordinary strings, so parsing a serialized preview cannot recover the
+    /// original, and cannot tell a marker from data that happens to match it.
+    pub fn preview(&self, options: &PreviewOptions) -> RdnValue {
+        match self {
+            RdnValue::String(s) => RdnValue::String(options.clip(s)),
+            RdnValue::Array(items) | RdnValue::Set(items) => {
+                let mut kept: Vec<RdnValue> =
+                    items.iter().take(options.max_array_elems).map(|item| item.preview(options)).collect();
+                if items.len() > kept.len() {
+                    kept.push(RdnValue::String(options.more(items.len() - kept.len())));
+                }
+                if matches!(self, RdnValue::Set(_)) {
+                    RdnValue::Set(kept)
+                } else {
+                    RdnValue::Array(kept)
+                }
+            }
+            RdnValue::Object(entries) => {
+                let mut kept: Vec<(String, RdnValue)> = entries
+                    .iter()
+                    .take(options.max_object_keys)
+                    .map(|(k, v)| (options.clip(k), v.preview(options)))
+                    .collect();
+                if entries.len() > kept.len() {
+                    kept.push((options.more(entries.len() - kept.len()), RdnValue::Null));
+                }
+                RdnValue::Object(kept)
+            }
+            RdnValue::Map(entries) => {
+                let mut kept: Vec<(RdnValue, RdnValue)> = entries
+                    .iter()
+                    .take(options.max_object_keys)
+                    .map(|(k, v)| (k.preview(options), v.preview(options)))
+                    .collect();
+                if entries.len() > kept.len() {
+                    kept.push((RdnValue::String(options.more(entries.len() - kept.len())), RdnValue::Null));
+                }
+                RdnValue::Map(kept)
+            }
+            other => other.clone(),
+        }
+    }
+
+    /// Returns a deep clone with every value matched by one of `patterns`
+    /// replaced by the string `marker`, for producing loggable copies of
+    /// sensitive documents.
+    ///
+    /// Patterns use the query syntax of [`RdnValue::select`], so
+    /// `redact(&["$.password", "$..secret"], "[REDACTED]")` masks the
+    /// top-level `password` and every `secret` member at any depth. Only
+    /// values are replaced; Map keys are never redacted. A value nested inside
+    /// one that was already redacted is covered by the outer marker.
+    ///
+    /// # Errors
+    ///
+    /// Fails on a malformed pattern rather than skipping it, since a silently
+    /// ignored pattern would leak the values it was meant to hide.
+    pub fn redact(&self, patterns: &[&str], marker: &str) -> Result<RdnValue, String> {
+        let mut redacted = self.clone();
+        for pattern in patterns {
+            for path in redacted.locate(pattern)? {
+                if let Some(target) = redacted.at_path_mut(&path) {
+                    *target = RdnValue::String(marker.to_string());
+                }
+            }
+        }
+        Ok(redacted)
+    }
+}
+
+#[cfg(feature = "unicode-normalization")]
+impl RdnValue {
+    /// Applies Unicode normalization `form` to every string value and key in
+    /// the tree, in place.
+    ///
+    /// Covers `String` values wherever they appear, Object keys, and Map keys
+    /// and Set members (which may themselves be strings). Other text, such as
+    /// RegExp sources, is left alone. This rewrites content: afterwards,
+    /// strings that were composed differently compare equal with `==` and
+    /// hash equally with [`RdnValue::short_hash`], so an Object may end up with
+    /// duplicate keys and a Set with duplicate members. Run
+    /// [`RdnValue::deduplicate_sets`] afterwards if Sets and Maps must stay
+    /// unique.
+    pub fn normalize_unicode(&mut self, form: NormalizationForm) {
+        match self {
+            RdnValue::String(s) => form.apply(s),
+            RdnValue::Array(items) | RdnValue::Set(items) => {
+                items.iter_mut().for_each(|item| item.normalize_unicode(form));
+            }
+            RdnValue::Object(entries) => entries.iter_mut().for_each(|(k, v)| {
+                form.apply(k);
+                v.normalize_unicode(form);
+            }),
+            RdnValue::Map(entries) => entries.iter_mut().for_each(|(k, v)| {
+                k.normalize_unicode(form);
+                v.normalize_unicode(form);
+            }),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{n, obj, s};
+
+    fn bits(value: &RdnValue) -> u64 {
+        match value {
+            RdnValue::Number(n) => n.to_bits(),
+            other => panic!("expected Number, got {}", other.type_name()),
+        }
+    }
+
+    #[test]
+    fn normalize_negative_zero() {
+        let mut v = RdnValue::Number(-0.0);
+        v.normalize_numbers();
+        assert_eq!(bits(&v), 0.0f64.to_bits());
+    }
+
+    #[test]
+    fn normalize_nan_payload() {
+        let mut v = RdnValue::Number(f64::from_bits(0x7ff8_0000_0000_beef));
+        v.normalize_numbers();
+        assert_eq!(bits(&v), f64::NAN.to_bits());
+    }
+
+    #[test]
+    fn normalize_leaves_integers_and_fractions() {
+        let mut v = RdnValue::Array(vec![RdnValue::Number(42.0), RdnValue::Number(4.2e1), RdnValue::Number(0.5)]);
+        v.normalize_numbers();
+        assert_eq!(v, RdnValue::Array(vec![RdnValue::Number(42.0), RdnValue::Number(42.0), RdnValue::Number(0.5)]));
+    }
+
+    #[test]
+    fn normalize_recurses_into_containers() {
+        let mut v = RdnValue::Object(vec![(
+            "m".to_string(),
+            RdnValue::Map(vec![(RdnValue::Number(-0.0), RdnValue::Set(vec![RdnValue::Number(-0.0)]))]),
+        )]);
+        v.normalize_numbers();
+        let RdnValue::Object(entries) = &v else { unreachable!() };
+        let RdnValue::Map(map) = &entries[0].1 else { unreachable!() };
+        assert_eq!(bits(&map[0].0), 0);
+        let RdnValue::Set(members) = &map[0].1 else { unreachable!() };
+        assert_eq!(bits(&members[0]), 0);
+    }
+
+    // --- deduplicate_sets tests ---
+
+    #[test]
+    fn dedup_set_keeps_first_occurrence_order() {
+        let mut v = RdnValue::Set(vec![n(3.0), n(1.0), n(3.0), n(2.0), n(1.0)]);
+        v.deduplicate_sets();
+        assert_eq!(v, RdnValue::Set(vec![n(3.0), n(1.0), n(2.0)]));
+    }
+
+    #[test]
+    fn dedup_set_uses_structural_equality() {
+        let mut v = RdnValue::Set(vec![
+            RdnValue::Array(vec![n(1.0)]),
+            RdnValue::Array(vec![n(1.0)]),
+            RdnValue::Array(vec![n(2.0)]),
+        ]);
+        v.deduplicate_sets();
+        assert_eq!(v, RdnValue::Set(vec![RdnValue::Array(vec![n(1.0)]), RdnValue::Array(vec![n(2.0)])]));
+    }
+
+    #[test]
+    fn dedup_map_last_value_wins_in_first_position() {
+        let mut v = RdnValue::Map(vec![(s("a"), n(1.0)), (s("b"), n(2.0)), (s("a"), n(3.0))]);
+        v.deduplicate_sets();
+        assert_eq!(v, RdnValue::Map(vec![(s("a"), n(3.0)), (s("b"), n(2.0))]));
+    }
+
+    #[test]
+    fn dedup_nested_sets_before_comparing() {
+        let mut v = RdnValue::Set(vec![
+            RdnValue::Set(vec![n(1.0), n(1.0)]),
+            RdnValue::Set(vec![n(1.0)]),
+        ]);
+        v.deduplicate_sets();
+        assert_eq!(v, RdnValue::Set(vec![RdnValue::Set(vec![n(1.0)])]));
+    }
+
+    #[test]
+    fn dedup_leaves_arrays_and_objects() {
+        let mut v = RdnValue::Object(vec![
+            ("a".to_string(), RdnValue::Array(vec![n(1.0), n(1.0)])),
+            ("a".to_string(), RdnValue::Set(vec![n(1.0), n(1.0)])),
+        ]);
+        v.deduplicate_sets();
+        assert_eq!(
+            v,
+            RdnValue::Object(vec![
+                ("a".to_string(), RdnValue::Array(vec![n(1.0), n(1.0)])),
+                ("a".to_string(), RdnValue::Set(vec![n(1.0)])),
+            ])
+        );
+    }
+
+    #[test]
+    fn dedup_set_collapses_nan_and_signed_zero() {
+        let mut v = RdnValue::Set(vec![n(f64::NAN), n(0.0), n(f64::NAN), n(-0.0)]);
+        v.deduplicate_sets();
+        let RdnValue::Set(members) = &v else { unreachable!() };
+        assert_eq!(members.len(), 2);
+        assert!(matches!(members[0], RdnValue::Number(x) if x.is_nan()));
+        assert_eq!(bits(&members[1]), 0.0f64.to_bits());
+    }
+
+    #[test]
+    fn dedup_map_nan_keys_are_one_key() {
+        let mut v = RdnValue::Map(vec![(n(f64::NAN), n(1.0)), (n(f64::NAN), n(2.0))]);
+        v.deduplicate_sets();
+        let RdnValue::Map(entries) = &v else { unreachable!() };
+        assert_eq!(entries.len(), 1);
+        assert!(matches!(entries[0].0, RdnValue::Number(x) if x.is_nan()));
+        assert_eq!(entries[0].1, n(2.0));
+    }
+
+    #[test]
+    fn dedup_nan_inside_nested_members() {
+        let mut v = RdnValue::Set(vec![RdnValue::Array(vec![n(f64::NAN)]), RdnValue::Array(vec![n(f64::NAN)])]);
+        v.deduplicate_sets();
+        let RdnValue::Set(members) = &v else { unreachable!() };
+        assert_eq!(members.len(), 1);
+    }
+
+    // --- prune_nulls tests ---
+
+    #[test]
+    fn prune_removes_null_entries_recursively() {
+        let mut v = obj(vec![
+            ("a", RdnValue::Null),
+            ("b", obj(vec![("c", RdnValue::Null), ("d", n(1.0))])),
+            ("m", RdnValue::Map(vec![(s("x"), RdnValue::Null), (RdnValue::Null, n(2.0))])),
+        ]);
+        v.prune_nulls(false);
+        assert_eq!(
+            v,
+            obj(vec![("b", obj(vec![("d", n(1.0))])), ("m", RdnValue::Map(vec![(RdnValue::Null, n(2.0))]))])
+        );
+    }
+
+    #[test]
+    fn prune_keeps_array_nulls_by_default() {
+        let mut v = RdnValue::Array(vec![RdnValue::Null, obj(vec![("a", RdnValue::Null)])]);
+        v.prune_nulls(false);
+        assert_eq!(v, RdnValue::Array(vec![RdnValue::Null, obj(vec![])]));
+    }
+
+    #[test]
+    fn prune_array_and_set_nulls_when_requested() {
+        let mut v = RdnValue::Array(vec![RdnValue::Null, n(1.0), RdnValue::Set(vec![RdnValue::Null, n(2.0)])]);
+        v.prune_nulls(true);
+        assert_eq!(v, RdnValue::Array(vec![n(1.0), RdnValue::Set(vec![n(2.0)])]));
+    }
+
+    // --- preview tests ---
+
+    fn small() -> PreviewOptions {
+        PreviewOptions { max_array_elems: 2, max_object_keys: 1, max_string_len: 3, ..PreviewOptions::default() }
+    }
+
+    #[test]
+    fn preview_truncates_containers() {
+        let v = obj(vec![
+            ("list", RdnValue::Array(vec![n(1.0), n(2.0), n(3.0), n(4.0)])),
+            ("rest", n(5.0)),
+            ("more", n(6.0)),
+        ]);
+        let expected = obj(vec![
+            ("lis…", RdnValue::Array(vec![n(1.0), n(2.0), s("…2 more")])),
+            ("…2 more", RdnValue::Null),
+        ]);
+        assert_eq!(v.preview(&small()), expected);
+    }
+
+    #[test]
+    fn preview_sets_maps_and_strings() {
+        let set = RdnValue::Set(vec![s("abcdef"), s("é"), s("x")]);
+        assert_eq!(set.preview(&small()), RdnValue::Set(vec![s("abc…"), s("é"), s("…1 more")]));
+        let map = RdnValue::Map(vec![(n(1.0), s("a")), (n(2.0), s("b"))]);
+        assert_eq!(map.preview(&small()), RdnValue::Map(vec![(n(1.0), s("a")), (s("…1 more"), RdnValue::Null)]));
+    }
+
+    #[test]
+    fn preview_custom_markers_and_small_values() {
+        let options = PreviewOptions { more_marker: "<+{n}>".to_string(), clip_marker: "~".to_string(), ..small() };
+        let v = RdnValue::Array(vec![s("hello"), n(1.0), n(2.0)]);
+        assert_eq!(v.preview(&options), RdnValue::Array(vec![s("hel~"), n(1.0), s("<+1>")]));
+        let fits = RdnValue::Array(vec![s("abc"), RdnValue::Binary(vec![0; 100])]);
+        assert_eq!(fits.preview(&small()), fits);
+    }
+
+    // --- redact tests ---
+
+    const MARKER: &str = "[REDACTED]";
+
+    #[test]
+    fn redact_masks_matching_paths() {
+        let v = obj(vec![
+            ("user", s("ada")),
+            ("password", s("hunter2")),
+            ("db", obj(vec![("host", s("db.local")), ("secret", n(42.0))])),
+            ("keys", RdnValue::Array(vec![obj(vec![("secret", s("k1"))])])),
+        ]);
+        let redacted = v.redact(&["$.password", "$..secret"], MARKER).unwrap();
+        assert_eq!(
+            redacted,
+            obj(vec![
+                ("user", s("ada")),
+                ("password", s(MARKER)),
+                ("db", obj(vec![("host", s("db.local")), ("secret", s(MARKER))])),
+                ("keys", RdnValue::Array(vec![obj(vec![("secret", s(MARKER))])])),
+            ])
+        );
+        assert_eq!(v.get_path("password"), Some(&s("hunter2")));
+    }
+
+    #[test]
+    fn redact_nested_matches_and_custom_marker() {
+        let v = obj(vec![("secret", obj(vec![("secret", s("inner"))]))]);
+        assert_eq!(v.redact(&["$..secret"], "***").unwrap(), obj(vec![("secret", s("***"))]));
+    }
+
+    #[test]
+    fn redact_leaves_map_keys() {
+        let v = RdnValue::Map(vec![(s("token"), s("abc"))]);
+        assert_eq!(v.redact(&["$.token"], MARKER).unwrap(), RdnValue::Map(vec![(s("token"), s(MARKER))]));
+    }
+
+    #[test]
+    fn redact_malformed_pattern_is_err() {
+        assert!(obj(vec![]).redact(&["password"], MARKER).is_err());
+        assert_eq!(obj(vec![]).redact(&["$.missing"], MARKER).unwrap(), obj(vec![]));
+    }
+
+    // --- normalize_unicode tests ---
+
+    #[cfg(feature = "unicode-normalization")]
+    #[test]
+    fn normalize_unicode_nfc_composes_values_and_keys() {
+        let decomposed = "cafe\u{301}";
+        let mut v = RdnValue::Object(vec![(decomposed.to_string(), RdnValue::Array(vec![s(decomposed)]))]);
+        v.normalize_unicode(NormalizationForm::Nfc);
+        assert_eq!(v, RdnValue::Object(vec![("caf\u{e9}".to_string(), RdnValue::Array(vec![s("caf\u{e9}")]))]));
+    }
+
+    #[cfg(feature = "unicode-normalization")]
+    #[test]
+    fn normalize_unicode_nfd_and_compatibility_forms() {
+        let mut v = RdnValue::Set(vec![s("\u{e9}")]);
+        v.normalize_unicode(NormalizationForm::Nfd);
+        assert_eq!(v, RdnValue::Set(vec![s("e\u{301}")]));
+        let mut v = RdnValue::Map(vec![(s("\u{fb01}"), s("\u{2460}"))]);
+        v.normalize_unicode(NormalizationForm::Nfkc);
+        assert_eq!(v, RdnValue::Map(vec![(s("fi"), s("1"))]));
+    }
+
+    #[cfg(feature = "unicode-normalization")]
+    #[test]
+    fn normalize_unicode_can_create_duplicates() {
+        let mut v = RdnValue::Set(vec![s("\u{e9}"), s("e\u{301}")]);
+        v.normalize_unicode(NormalizationForm::Nfc);
+        v.deduplicate_sets();
+        assert_eq!(v, RdnValue::Set(vec![s("\u{e9}")]));
+    }
+}
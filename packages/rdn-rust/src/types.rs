@@ -1,12 +1,17 @@
 use std::fmt;
 
+use crate::convert::exact_i64;
+use crate::date::parse_iso_datetime;
+use crate::serializer::{write_rdn, Indent, StringifyOptions};
+
 /// Represents any RDN value.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub enum RdnValue {
     Null,
     Bool(bool),
     Number(f64),
     BigInt(BigInt),
+    Decimal(Decimal),
     String(String),
     Array(Vec<RdnValue>),
     Object(Vec<(String, RdnValue)>),
@@ -19,6 +24,65 @@ pub enum RdnValue {
     Set(Vec<RdnValue>),
 }
 
+impl RdnValue {
+    /// Returns the name of this value's variant, e.g. `"Number"` or `"Map"`.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            RdnValue::Null => "Null",
+            RdnValue::Bool(_) => "Bool",
+            RdnValue::Number(_) => "Number",
+            RdnValue::BigInt(_) => "BigInt",
+            RdnValue::Decimal(_) => "Decimal",
+            RdnValue::String(_) => "String",
+            RdnValue::Array(_) => "Array",
+            RdnValue::Object(_) => "Object",
+            RdnValue::Date(_) => "Date",
+            RdnValue::TimeOnly(_) => "TimeOnly",
+            RdnValue::Duration(_) => "Duration",
+            RdnValue::RegExp(_) => "RegExp",
+            RdnValue::Binary(_) => "Binary",
+            RdnValue::Map(_) => "Map",
+            RdnValue::Set(_) => "Set",
+        }
+    }
+
+    /// Creates an empty `Object` with room for `capacity` entries.
+    pub fn object_with_capacity(capacity: usize) -> Self {
+        RdnValue::Object(Vec::with_capacity(capacity))
+    }
+
+    /// Creates an empty `Array` with room for `capacity` elements.
+    pub fn array_with_capacity(capacity: usize) -> Self {
+        RdnValue::Array(Vec::with_capacity(capacity))
+    }
+
+    /// Returns the UTF-8 byte length of a `String`, or `None` for other types.
+    pub fn str_byte_len(&self) -> Option<usize> {
+        match self {
+            RdnValue::String(s) => Some(s.len()),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of Unicode scalar values in a `String`, or `None`
+    /// for other types. This can be smaller than [`RdnValue::str_byte_len`]
+    /// and larger than the number of user-perceived characters.
+    pub fn str_char_len(&self) -> Option<usize> {
+        match self {
+            RdnValue::String(s) => Some(s.chars().count()),
+            _ => None,
+        }
+    }
+
+    /// Returns the byte length of a `Binary`, or `None` for other types.
+    pub fn binary_len(&self) -> Option<usize> {
+        match self {
+            RdnValue::Binary(bytes) => Some(bytes.len()),
+            _ => None,
+        }
+    }
+}
+
 /// Arbitrary-precision integer (stored as string for now).
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct BigInt {
@@ -49,12 +113,74 @@ impl BigInt {
     }
 }
 
+/// Arbitrary-precision decimal (stored as string, like `BigInt`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decimal {
+    value: String,
+}
+
+impl Decimal {
+    /// Creates a new `Decimal` from a string value.
+    ///
+    /// The value must have an optional leading `-`, one or more ASCII digits,
+    /// and optionally a single `.` followed by one or more ASCII digits.
+    pub fn new(value: &str) -> Result<Self, String> {
+        if value.is_empty() {
+            return Err("Decimal value must not be empty".to_string());
+        }
+        let unsigned = if let Some(rest) = value.strip_prefix('-') { rest } else { value };
+        let (int, frac) = match unsigned.split_once('.') {
+            Some((int, frac)) => (int, Some(frac)),
+            None => (unsigned, None),
+        };
+        if int.is_empty() || frac.is_some_and(str::is_empty) {
+            return Err(format!("Decimal value must have digits on both sides of the point: {value}"));
+        }
+        if !int.chars().chain(frac.unwrap_or("").chars()).all(|c| c.is_ascii_digit()) {
+            return Err(format!("Decimal value contains non-digit characters: {value}"));
+        }
+        Ok(Decimal { value: value.to_string() })
+    }
+
+    /// Returns the string representation of this Decimal.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+}
+
 /// A date/time value (milliseconds since Unix epoch).
 #[derive(Debug, Clone, PartialEq)]
 pub struct RdnDate {
     pub millis: f64,
 }
 
+impl RdnDate {
+    /// Parses a standalone ISO 8601 date or date-time, without the leading `@`.
+    ///
+    /// Accepts the same forms as a Date literal in a document: a calendar,
+    /// week, or ordinal date, optionally followed by `THH:MM:SS[.fraction]`
+    /// and a `Z` or `±HH:MM` offset. Offsets are folded into the UTC millis.
+    pub fn parse_iso(s: &str) -> Result<Self, String> {
+        parse_iso_datetime(s).map(|millis| RdnDate { millis })
+    }
+
+    /// Creates a date from whole milliseconds since the Unix epoch.
+    ///
+    /// Every instant within ±2^53 ms (about ±285,000 years) is stored exactly;
+    /// beyond that the value rounds to the nearest representable `f64`.
+    pub fn from_millis_i64(millis: i64) -> Self {
+        RdnDate { millis: millis as f64 }
+    }
+
+    /// Returns the milliseconds since the Unix epoch as an integer, truncating
+    /// any sub-millisecond fraction toward zero.
+    ///
+    /// Returns `None` if `millis` is not finite or is outside the `i64` range.
+    pub fn millis_i64(&self) -> Option<i64> {
+        exact_i64(self.millis.trunc())
+    }
+}
+
 /// A time-of-day value.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RdnTimeOnly {
@@ -99,6 +225,76 @@ pub struct RdnDuration {
     pub iso: String,
 }
 
+impl RdnDuration {
+    /// Creates a new `RdnDuration` after validating the ISO 8601 shape.
+    ///
+    /// The value is `P`, then optional date components in the order `Y`, `M`,
+    /// `W`, `D`, then optionally `T` followed by time components in the order
+    /// `H`, `M`, `S`. Each component is one or more digits; only seconds may
+    /// carry a fraction (`PT0.5S`). At least one component is required, and a
+    /// `T` must be followed by at least one time component, so `P`, `PT`, and
+    /// `P1DT` are rejected. `M` means months before the `T` and minutes after
+    /// it, so `P5M` and `PT5M` are different durations; the string is stored
+    /// exactly as given.
+    pub fn new(iso: &str) -> Result<Self, String> {
+        let body = iso
+            .strip_prefix('P')
+            .ok_or_else(|| format!("duration must start with 'P': {iso}"))?;
+        let (date_part, time_part) = match body.split_once('T') {
+            Some((date, time)) => (date, Some(time)),
+            None => (body, None),
+        };
+        let date_count = Self::validate_components(date_part, &['Y', 'M', 'W', 'D'], iso)?;
+        let time_count = match time_part {
+            Some(time) => {
+                let count = Self::validate_components(time, &['H', 'M', 'S'], iso)?;
+                if count == 0 {
+                    return Err(format!("duration has 'T' but no time components: {iso}"));
+                }
+                count
+            }
+            None => 0,
+        };
+        if date_count + time_count == 0 {
+            return Err(format!("duration has no components: {iso}"));
+        }
+        Ok(RdnDuration { iso: iso.to_string() })
+    }
+
+    /// Validates a run of `<digits><designator>` components whose designators
+    /// must appear in the order given, returning how many were present.
+    fn validate_components(part: &str, designators: &[char], iso: &str) -> Result<usize, String> {
+        let mut next_allowed = 0;
+        let mut count = 0;
+        let mut rest = part;
+        while !rest.is_empty() {
+            let number_len = rest.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(rest.len());
+            let number = &rest[..number_len];
+            let designator = rest[number_len..]
+                .chars()
+                .next()
+                .ok_or_else(|| format!("duration component '{number}' has no designator: {iso}"))?;
+            let (int_part, fraction) = match number.split_once('.') {
+                Some((int_part, fraction)) => (int_part, Some(fraction)),
+                None => (number, None),
+            };
+            if int_part.is_empty() || fraction.is_some_and(|f| f.is_empty() || f.contains('.')) {
+                return Err(format!("invalid number '{number}' in duration: {iso}"));
+            }
+            if fraction.is_some() && designator != 'S' {
+                return Err(format!("only seconds may be fractional in duration: {iso}"));
+            }
+            match designators[next_allowed..].iter().position(|&d| d == designator) {
+                Some(offset) => next_allowed += offset + 1,
+                None => return Err(format!("unexpected '{designator}' in duration: {iso}")),
+            }
+            count += 1;
+            rest = &rest[number_len + designator.len_utf8()..];
+        }
+        Ok(count)
+    }
+}
+
 /// A regular expression with pattern and flags.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RdnRegExp {
@@ -111,6 +307,16 @@ impl RdnRegExp {
     ///
     /// Flags must only contain characters from `d`, `g`, `i`, `m`, `s`, `u`, `v`, `y`,
     /// and each flag may appear at most once.
+    ///
+    /// The source is stored the way JavaScript's `RegExp.prototype.source`
+    /// reports it, so that `/source/flags` is always a valid literal:
+    ///
+    /// - A `/` outside a character class is escaped as `\/`. One inside a
+    ///   class, as in `[a/b]`, or already escaped is kept as is.
+    /// - An empty source becomes `(?:)`, which matches the same thing, since
+    ///   `//` is not a literal.
+    /// - A source ending in a lone `\`, or with a `[` that is never closed,
+    ///   is an error, as it is for JavaScript's `RegExp` constructor.
     pub fn new(source: &str, flags: &str) -> Result<Self, String> {
         const VALID_FLAGS: &[char] = &['d', 'g', 'i', 'm', 's', 'u', 'v', 'y'];
         let mut seen = [false; 8];
@@ -125,55 +331,85 @@ impl RdnRegExp {
                 None => return Err(format!("invalid regex flag: {ch}")),
             }
         }
-        Ok(RdnRegExp { source: source.to_string(), flags: flags.to_string() })
+        Ok(RdnRegExp { source: canonical_source(source)?, flags: flags.to_string() })
     }
 
     pub fn source(&self) -> &str { &self.source }
     pub fn flags(&self) -> &str { &self.flags }
 }
 
-/// Writes `s` to the formatter as a properly escaped RDN/JSON string
-/// (including the surrounding double quotes).
-fn write_escaped_string(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
-    f.write_str("\"")?;
-    for ch in s.chars() {
+/// Escapes the `/`s in a regex source that would end a `/.../` literal;
+/// see [`RdnRegExp::new`].
+fn canonical_source(source: &str) -> Result<String, String> {
+    if source.is_empty() {
+        return Ok("(?:)".to_string());
+    }
+    let mut out = String::with_capacity(source.len());
+    let mut in_class = false;
+    let mut chars = source.chars();
+    while let Some(ch) = chars.next() {
         match ch {
-            '"' => f.write_str("\\\"")?,
-            '\\' => f.write_str("\\\\")?,
-            '\n' => f.write_str("\\n")?,
-            '\r' => f.write_str("\\r")?,
-            '\t' => f.write_str("\\t")?,
-            '\u{08}' => f.write_str("\\b")?,
-            '\u{0C}' => f.write_str("\\f")?,
-            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
-            c => f.write_str(&c.to_string())?,
+            '\\' => {
+                let Some(escaped) = chars.next() else {
+                    return Err("regex pattern ends with a lone backslash".to_string());
+                };
+                out.push('\\');
+                out.push(escaped);
+                continue;
+            }
+            '[' => in_class = true,
+            ']' => in_class = false,
+            '/' if !in_class => {
+                out.push_str("\\/");
+                continue;
+            }
+            _ => {}
         }
+        out.push(ch);
     }
-    f.write_str("\"")
+    if in_class {
+        return Err("unterminated character class in regex pattern".to_string());
+    }
+    Ok(out)
 }
 
+#[cfg(feature = "regex")]
+impl RdnRegExp {
+    /// Compiles the pattern with the `regex` crate.
+    ///
+    /// The `i`, `m`, and `s` flags map onto the equivalent builder options. The
+    /// remaining JavaScript flags (`d`, `g`, `u`, `v`, `y`) describe matching
+    /// behavior rather than syntax and are ignored here.
+    pub fn compile(&self) -> Result<regex::Regex, String> {
+        regex::RegexBuilder::new(&self.source)
+            .case_insensitive(self.flags.contains('i'))
+            .multi_line(self.flags.contains('m'))
+            .dot_matches_new_line(self.flags.contains('s'))
+            .build()
+            .map_err(|e| format!("invalid regex pattern /{}/: {e}", self.source))
+    }
+
+    /// Checks that the pattern compiles, discarding the compiled regex.
+    ///
+    /// Returns the same error message as [`RdnRegExp::compile`].
+    pub fn validate_pattern(&self) -> Result<(), String> {
+        self.compile().map(|_| ())
+    }
+
+    /// Returns `true` if the pattern is a syntactically valid Rust regex.
+    pub fn is_valid_pattern(&self) -> bool {
+        self.validate_pattern().is_ok()
+    }
+}
+
+/// Formats the value as RDN text, exactly as [`stringify`](crate::stringify)
+/// writes it. The alternate form, `{:#}`, is pretty-printed with an indent of
+/// two spaces, as by [`stringify_pretty`](crate::stringify_pretty).
 impl fmt::Display for RdnValue {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            RdnValue::Null => write!(f, "null"),
-            RdnValue::Bool(b) => write!(f, "{b}"),
-            RdnValue::Number(n) => {
-                if n.is_nan() {
-                    write!(f, "NaN")
-                } else if n.is_infinite() {
-                    if n.is_sign_positive() {
-                        write!(f, "Infinity")
-                    } else {
-                        write!(f, "-Infinity")
-                    }
-                } else {
-                    write!(f, "{n}")
-                }
-            }
-            RdnValue::BigInt(bi) => write!(f, "{}n", bi.value()),
-            RdnValue::String(s) => write_escaped_string(f, s),
-            _ => write!(f, "[RdnValue]"),
-        }
+        let indent = if f.alternate() { Indent::Spaces(2) } else { Indent::None };
+        let options = StringifyOptions { indent, ..StringifyOptions::default() };
+        write_rdn(f, self, &options)
     }
 }
 
@@ -185,6 +421,42 @@ mod tests {
         RdnValue::String(s.to_string())
     }
 
+    // --- Display for every variant ---
+
+    #[test]
+    fn display_containers_as_rdn() {
+        let array = RdnValue::Array(vec![RdnValue::Number(1.0), str_val("a")]);
+        let object = RdnValue::Object(vec![("k".to_string(), array.clone())]);
+        let map = RdnValue::Map(vec![(RdnValue::Null, RdnValue::Set(vec![RdnValue::Bool(true)]))]);
+        let set = RdnValue::Set(vec![object.clone(), RdnValue::Map(Vec::new())]);
+        assert_eq!(array.to_string(), r#"[1,"a"]"#);
+        assert_eq!(object.to_string(), r#"{"k":[1,"a"]}"#);
+        assert_eq!(map.to_string(), "Map{null=>Set{true}}");
+        assert_eq!(set.to_string(), r#"Set{{"k":[1,"a"]},Map{}}"#);
+        for value in [array, object, map, set] {
+            assert_eq!(crate::parse(&value.to_string()).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn display_extended_types_as_rdn() {
+        let date = RdnValue::Date(RdnDate::parse_iso("2024-01-15T10:30:00.250Z").unwrap());
+        let regexp = RdnValue::RegExp(RdnRegExp::new("^a/b$", "gi").unwrap());
+        let binary = RdnValue::Binary(vec![1, 2, 3]);
+        assert_eq!(date.to_string(), "@2024-01-15T10:30:00.250Z");
+        assert_eq!(regexp.to_string(), r"/^a\/b$/gi");
+        assert_eq!(binary.to_string(), r#"b"AQID""#);
+        for value in [date, regexp, binary] {
+            assert_eq!(crate::parse(&value.to_string()).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn display_alternate_is_pretty() {
+        let value = RdnValue::Object(vec![("a".to_string(), RdnValue::Array(vec![RdnValue::Null]))]);
+        assert_eq!(format!("{value:#}"), "{\n  \"a\": [\n    null\n  ]\n}");
+    }
+
     #[test]
     fn display_normal_string() {
         assert_eq!(str_val("hello").to_string(), r#""hello""#);
@@ -226,6 +498,104 @@ mod tests {
         assert_eq!(str_val("\u{08}\u{0C}").to_string(), r#""\b\f""#);
     }
 
+    #[test]
+    fn display_string_escapes_line_and_paragraph_separators() {
+        assert_eq!(str_val("a\u{2028}b\u{2029}c").to_string(), r#""a\u2028b\u2029c""#);
+    }
+
+    #[test]
+    fn display_string_keeps_other_non_ascii_raw() {
+        assert_eq!(str_val("caf\u{e9} \u{2027}").to_string(), "\"caf\u{e9} \u{2027}\"");
+    }
+
+    #[test]
+    fn display_string_keeps_in_string_bom() {
+        assert_eq!(str_val("\u{FEFF}a\u{FEFF}").to_string(), "\"\u{FEFF}a\u{FEFF}\"");
+    }
+
+    // --- Pre-sized constructor tests ---
+
+    #[test]
+    fn object_with_capacity_reserves() {
+        let RdnValue::Object(entries) = RdnValue::object_with_capacity(16) else { unreachable!() };
+        assert!(entries.is_empty());
+        assert!(entries.capacity() >= 16);
+    }
+
+    #[test]
+    fn array_with_capacity_reserves() {
+        let RdnValue::Array(items) = RdnValue::array_with_capacity(8) else { unreachable!() };
+        assert!(items.is_empty());
+        assert!(items.capacity() >= 8);
+    }
+
+    // --- Length helper tests ---
+
+    #[test]
+    fn str_lengths_count_bytes_and_chars() {
+        let v = str_val("h\u{e9}llo \u{1F600}");
+        assert_eq!(v.str_byte_len(), Some(11));
+        assert_eq!(v.str_char_len(), Some(7));
+    }
+
+    #[test]
+    fn length_helpers_are_none_for_other_types() {
+        assert_eq!(RdnValue::Binary(vec![0; 3]).str_byte_len(), None);
+        assert_eq!(RdnValue::Null.str_char_len(), None);
+        assert_eq!(str_val("abc").binary_len(), None);
+    }
+
+    #[test]
+    fn binary_len_counts_bytes() {
+        assert_eq!(RdnValue::Binary(vec![0; 3]).binary_len(), Some(3));
+    }
+
+    // --- RdnDate::parse_iso tests ---
+
+    #[test]
+    fn date_parse_iso_utc() {
+        let d = RdnDate::parse_iso("2024-01-15T10:30:00.123Z").unwrap();
+        assert_eq!(d.millis, 1_705_314_600_123.0);
+    }
+
+    #[test]
+    fn date_parse_iso_offset() {
+        let d = RdnDate::parse_iso("2024-01-15T12:30:00+02:00").unwrap();
+        assert_eq!(d.millis, 1_705_314_600_000.0);
+    }
+
+    #[test]
+    fn date_parse_iso_date_only() {
+        assert_eq!(RdnDate::parse_iso("2024-01-15").unwrap().millis, 1_705_276_800_000.0);
+    }
+
+    #[test]
+    fn date_parse_iso_rejects_prefix_and_bad_fields() {
+        assert!(RdnDate::parse_iso("@2024-01-15").is_err());
+        assert!(RdnDate::parse_iso("2024-02-30").is_err());
+    }
+
+    #[test]
+    fn date_millis_i64_round_trip() {
+        let d = RdnDate::from_millis_i64(1_705_314_600_123);
+        assert_eq!(d.millis, 1_705_314_600_123.0);
+        assert_eq!(d.millis_i64(), Some(1_705_314_600_123));
+        assert_eq!(RdnDate::from_millis_i64(-1).millis_i64(), Some(-1));
+    }
+
+    #[test]
+    fn date_millis_i64_truncates_fraction() {
+        assert_eq!(RdnDate { millis: 1.9 }.millis_i64(), Some(1));
+        assert_eq!(RdnDate { millis: -1.9 }.millis_i64(), Some(-1));
+    }
+
+    #[test]
+    fn date_millis_i64_rejects_non_finite_and_huge() {
+        assert_eq!(RdnDate { millis: f64::NAN }.millis_i64(), None);
+        assert_eq!(RdnDate { millis: f64::INFINITY }.millis_i64(), None);
+        assert_eq!(RdnDate { millis: 1e19 }.millis_i64(), None);
+    }
+
     // --- BigInt validation tests ---
 
     #[test]
@@ -273,6 +643,35 @@ mod tests {
         assert!(BigInt::new("1.5").is_err());
     }
 
+    // --- Decimal validation tests ---
+
+    #[test]
+    fn decimal_valid_forms() {
+        assert_eq!(Decimal::new("3.14").unwrap().value(), "3.14");
+        assert_eq!(Decimal::new("-0.50").unwrap().value(), "-0.50");
+        assert_eq!(Decimal::new("42").unwrap().value(), "42");
+    }
+
+    #[test]
+    fn decimal_display() {
+        assert_eq!(RdnValue::Decimal(Decimal::new("19.99").unwrap()).to_string(), "19.99m");
+    }
+
+    #[test]
+    fn decimal_missing_digits_is_err() {
+        assert!(Decimal::new("").is_err());
+        assert!(Decimal::new("-").is_err());
+        assert!(Decimal::new(".5").is_err());
+        assert!(Decimal::new("5.").is_err());
+    }
+
+    #[test]
+    fn decimal_malformed_is_err() {
+        assert!(Decimal::new("1.2.3").is_err());
+        assert!(Decimal::new("1e5").is_err());
+        assert!(Decimal::new("+1.0").is_err());
+    }
+
     // --- RdnTimeOnly validation tests ---
 
     #[test]
@@ -317,6 +716,57 @@ mod tests {
         assert!(RdnTimeOnly::new(12, 30, 0, 1000).is_err());
     }
 
+    // --- RdnDuration validation tests ---
+
+    #[test]
+    fn duration_valid_full() {
+        assert_eq!(RdnDuration::new("P1Y2M3DT4H5M6S").unwrap().iso, "P1Y2M3DT4H5M6S");
+    }
+
+    #[test]
+    fn duration_time_only_components() {
+        assert_eq!(RdnDuration::new("PT5M").unwrap().iso, "PT5M");
+        assert_eq!(RdnDuration::new("PT0S").unwrap().iso, "PT0S");
+        assert_eq!(RdnDuration::new("P0D").unwrap().iso, "P0D");
+    }
+
+    #[test]
+    fn duration_months_and_minutes_are_distinct() {
+        let months = RdnDuration::new("P5M").unwrap();
+        let minutes = RdnDuration::new("PT5M").unwrap();
+        assert_eq!(months.iso, "P5M");
+        assert_eq!(minutes.iso, "PT5M");
+        assert_ne!(months, minutes);
+    }
+
+    #[test]
+    fn duration_weeks_and_fractional_seconds() {
+        assert!(RdnDuration::new("P3W").is_ok());
+        assert!(RdnDuration::new("PT0.5S").is_ok());
+        assert!(RdnDuration::new("PT1.5M").is_err());
+        assert!(RdnDuration::new("PT.5S").is_err());
+        assert!(RdnDuration::new("PT1.S").is_err());
+    }
+
+    #[test]
+    fn duration_requires_components() {
+        assert!(RdnDuration::new("P").is_err());
+        assert!(RdnDuration::new("PT").is_err());
+        assert!(RdnDuration::new("P1DT").is_err());
+    }
+
+    #[test]
+    fn duration_rejects_bad_order_and_designators() {
+        assert!(RdnDuration::new("P1D2Y").is_err());
+        assert!(RdnDuration::new("P1Y1Y").is_err());
+        assert!(RdnDuration::new("PT1S2H").is_err());
+        assert!(RdnDuration::new("P1H").is_err());
+        assert!(RdnDuration::new("PT1D").is_err());
+        assert!(RdnDuration::new("P1").is_err());
+        assert!(RdnDuration::new("1D").is_err());
+        assert!(RdnDuration::new("P1DT2HT3M").is_err());
+    }
+
     // --- RdnRegExp validation tests ---
 
     #[test]
@@ -349,4 +799,56 @@ mod tests {
         assert!(RdnRegExp::new(".", "gg").is_err());
         assert!(RdnRegExp::new(".", "gig").is_err());
     }
+
+    #[test]
+    fn regexp_escapes_delimiter_slashes() {
+        let source = |s: &str| RdnRegExp::new(s, "").unwrap().source().to_string();
+        assert_eq!(source("a/b"), r"a\/b");
+        assert_eq!(source(r"a\/b"), r"a\/b");
+        assert_eq!(source("[a/b]/c"), r"[a/b]\/c");
+        assert_eq!(source(r"[\]/]/"), r"[\]/]\/");
+        assert_eq!(source(r"\\/"), r"\\\/");
+        assert_eq!(source(""), "(?:)");
+    }
+
+    #[test]
+    fn regexp_rejects_unfinished_source() {
+        assert_eq!(RdnRegExp::new("a\\", "").unwrap_err(), "regex pattern ends with a lone backslash");
+        assert_eq!(RdnRegExp::new("[a/", "").unwrap_err(), "unterminated character class in regex pattern");
+        assert!(RdnRegExp::new(r"a\\", "").is_ok());
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn regexp_valid_pattern() {
+        let re = RdnRegExp::new("^[a-z]+\\d{2,}$", "i").unwrap();
+        assert!(re.is_valid_pattern());
+        assert!(re.validate_pattern().is_ok());
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn regexp_invalid_pattern() {
+        let re = RdnRegExp::new("(unclosed", "").unwrap();
+        assert!(!re.is_valid_pattern());
+        let err = re.validate_pattern().unwrap_err();
+        assert!(err.contains("/(unclosed/"), "{err}");
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn regexp_compile_applies_flags() {
+        let re = RdnRegExp::new("^abc$", "im").unwrap().compile().unwrap();
+        assert!(re.is_match("x\nABC\ny"));
+        let re = RdnRegExp::new("a.b", "s").unwrap().compile().unwrap();
+        assert!(re.is_match("a\nb"));
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn regexp_compile_canonical_source() {
+        let re = RdnRegExp::new("^a/b$", "").unwrap().compile().unwrap();
+        assert!(re.is_match("a/b"));
+        assert!(RdnRegExp::new("", "").unwrap().compile().unwrap().is_match("anything"));
+    }
 }